@@ -0,0 +1,114 @@
+use object_store::ObjectStore;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use crate::aws::S3Config;
+use crate::BucketStoreConfig;
+
+// Cache of built stores, so that registering the same bucket over and over reuses a single
+// client (and its connection pool) instead of building a fresh one each time.
+//
+// Stores are keyed by the whole config, credentials included, so only structurally equal
+// configs ever share a store.
+#[derive(Debug)]
+pub struct StoreCache<C = S3Config> {
+    stores: Mutex<HashMap<C, Arc<dyn ObjectStore>>>,
+}
+
+impl<C> Default for StoreCache<C> {
+    fn default() -> Self {
+        Self {
+            stores: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<C: BucketStoreConfig + Hash + Eq + Clone> StoreCache<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_or_build(
+        &self,
+        config: &C,
+    ) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
+        let mut stores = self.stores.lock().unwrap();
+        if let Some(store) = stores.get(config) {
+            return Ok(store.clone());
+        }
+
+        let store = config.build()?;
+        stores.insert(config.clone(), store.clone());
+        Ok(store)
+    }
+
+    pub fn len(&self) -> usize {
+        self.stores.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.stores.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::google::GCSConfig;
+
+    fn s3_config() -> S3Config {
+        S3Config::builder()
+            .bucket("my-bucket")
+            .region("us-east-1")
+            .credentials("access_key", "secret_key")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_identical_configs_share_a_store() {
+        let cache = StoreCache::new();
+
+        let store = cache.get_or_build(&s3_config()).unwrap();
+        let other_store = cache.get_or_build(&s3_config()).unwrap();
+
+        assert!(Arc::ptr_eq(&store, &other_store));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_different_configs_get_different_stores() {
+        let cache = StoreCache::new();
+
+        let store = cache.get_or_build(&s3_config()).unwrap();
+        let other_store = cache
+            .get_or_build(&S3Config {
+                secret_access_key: Some("other_secret_key".to_string()),
+                ..s3_config()
+            })
+            .unwrap();
+
+        assert!(!Arc::ptr_eq(&store, &other_store));
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_gcs_store_cache() {
+        let cache = StoreCache::<GCSConfig>::new();
+        let config = GCSConfig {
+            bucket: "my-bucket".to_string(),
+            ..Default::default()
+        };
+
+        let store = cache.get_or_build(&config).unwrap();
+        assert!(Arc::ptr_eq(&store, &cache.get_or_build(&config).unwrap()));
+    }
+}
@@ -0,0 +1,404 @@
+use futures::StreamExt;
+use object_store::{path::Path, Error, ObjectMeta, ObjectStore};
+
+// Perform the cheapest possible authenticated request against the store (fetching at most the
+// first listing page) to confirm that the bucket exists and the credentials are valid.
+pub(crate) async fn check_store_access(
+    store: &dyn ObjectStore,
+    prefix: Option<&Path>,
+    location: &str,
+) -> Result<(), Error> {
+    match store.list(prefix).next().await {
+        None | Some(Ok(_)) => Ok(()),
+        Some(Err(err)) => Err(classify_access_error(location, err)),
+    }
+}
+
+// Head `key` under the prefix, to confirm end to end (credentials, region, prefix) that a
+// known object can be reached. Not found and access errors get their own messages, as in
+// `check_store_access`.
+pub(crate) async fn check_object_exists(
+    store: &dyn ObjectStore,
+    prefix: Option<&Path>,
+    key: &str,
+    bucket_url: &str,
+) -> Result<ObjectMeta, Error> {
+    let path = match prefix {
+        Some(prefix) => Path::from(format!("{prefix}/{key}")),
+        None => Path::from(key),
+    };
+    let location = format!("{}/{path}", bucket_url.trim_end_matches('/'));
+
+    store.head(&path).await.map_err(|err| {
+        if is_auth_error(&err) {
+            access_denied(&location, err)
+        } else if is_not_found(&err, &["404", "NoSuchKey", "NoSuchBucket"]) {
+            Error::NotFound {
+                path: location.clone(),
+                source: format!(
+                    "Object {location} not found, check the bucket, prefix and key: {err}"
+                )
+                .into(),
+            }
+        } else {
+            err
+        }
+    })
+}
+
+// List `prefix` under the base path like a directory: the common prefixes (sub-directories)
+// and the objects right in it, with all paths relative to the base path so that they can be
+// listed in turn
+pub(crate) async fn list_directory(
+    store: &dyn ObjectStore,
+    base: Option<&Path>,
+    prefix: &Path,
+) -> Result<(Vec<Path>, Vec<ObjectMeta>), Error> {
+    let directory: Path = base
+        .into_iter()
+        .flat_map(Path::parts)
+        .chain(prefix.parts())
+        .collect();
+    let relative = |path: Path| {
+        base.and_then(|base| path.prefix_match(base).map(|parts| parts.collect::<Path>()))
+            .unwrap_or(path)
+    };
+
+    let listing = store
+        .list_with_delimiter((directory != Path::default()).then_some(&directory))
+        .await?;
+    let directories = listing.common_prefixes.into_iter().map(relative).collect();
+    let files = listing
+        .objects
+        .into_iter()
+        .map(|meta| ObjectMeta {
+            location: relative(meta.location),
+            ..meta
+        })
+        .collect();
+    Ok((directories, files))
+}
+
+// Errors are mostly reported as `Error::Generic`, so besides the dedicated variants we also
+// need to look for the status codes/error codes in the message.
+fn is_auth_error(err: &Error) -> bool {
+    const AUTH_MARKERS: &[&str] = &[
+        "401",
+        "403",
+        "AccessDenied",
+        "InvalidAccessKeyId",
+        "SignatureDoesNotMatch",
+    ];
+
+    match err {
+        Error::PermissionDenied { .. } | Error::Unauthenticated { .. } => true,
+        Error::NotFound { .. } => false,
+        other => {
+            let message = other.to_string();
+            AUTH_MARKERS.iter().any(|marker| message.contains(marker))
+        }
+    }
+}
+
+fn is_not_found(err: &Error, markers: &[&str]) -> bool {
+    match err {
+        Error::NotFound { .. } => true,
+        other => {
+            let message = other.to_string();
+            markers.iter().any(|marker| message.contains(marker))
+        }
+    }
+}
+
+fn access_denied(location: &str, err: Error) -> Error {
+    Error::PermissionDenied {
+        path: location.to_string(),
+        source: format!(
+            "Access to {location} was denied, check the configured credentials: {err}"
+        )
+        .into(),
+    }
+}
+
+fn classify_access_error(location: &str, err: Error) -> Error {
+    if is_auth_error(&err) {
+        return access_denied(location, err);
+    }
+    if is_not_found(&err, &["404", "NoSuchBucket"]) {
+        return Error::NotFound {
+            path: location.to_string(),
+            source: format!(
+                "Bucket or prefix {location} not found, check the bucket name and endpoint: {err}"
+            )
+            .into(),
+        };
+    }
+
+    err
+}
+
+// Whether retrying the failed operation could help, for callers running their own retry loops
+// on top of the built stores. Transient failures are network errors, throttling and 5xx
+// responses (including those object_store already gave up retrying); anything pointing at the
+// request itself or the config (not found, access denied, failed preconditions, invalid
+// paths or keys) is fatal.
+pub fn is_retryable(err: &Error) -> bool {
+    const RETRYABLE_MARKERS: &[&str] = &[
+        "429 Too Many Requests",
+        "500 Internal Server Error",
+        "502 Bad Gateway",
+        "503 Service Unavailable",
+        "504 Gateway Timeout",
+        "SlowDown",
+        "InternalError",
+        "ServiceUnavailable",
+        "RequestTimeout",
+        "error sending request",
+        "connection",
+        "timed out",
+        // See `TimeoutStore`
+        "did not complete within",
+    ];
+
+    match err {
+        Error::Generic { .. } => {
+            if is_auth_error(err)
+                || is_not_found(err, &["404", "NoSuchKey", "NoSuchBucket"])
+            {
+                return false;
+            }
+            let message = err.to_string();
+            RETRYABLE_MARKERS
+                .iter()
+                .any(|marker| message.contains(marker))
+        }
+        _ => false,
+    }
+}
+
+// Whether the request never reached the server (connection refused or reset, DNS failures),
+// as opposed to the server answering with an error. Narrower than `is_retryable`, which also
+// counts throttling and 5xx responses.
+pub(crate) fn is_connection_error(err: &Error) -> bool {
+    const CONNECTION_MARKERS: &[&str] = &[
+        "error sending request",
+        "tcp connect error",
+        "dns error",
+        "Connection refused",
+        "connection reset",
+    ];
+
+    match err {
+        Error::Generic { .. } => {
+            let message = err.to_string();
+            CONNECTION_MARKERS
+                .iter()
+                .any(|marker| message.contains(marker))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use object_store::PutPayload;
+    use rstest::rstest;
+
+    #[tokio::test]
+    async fn test_check_store_access() {
+        let store = InMemory::new();
+        check_store_access(&store, None, "memory:///")
+            .await
+            .unwrap();
+
+        store
+            .put(&Path::from("prefix/file"), PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        check_store_access(&store, Some(&Path::from("prefix")), "memory:///prefix")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_object_exists() {
+        let store = InMemory::new();
+        store
+            .put(
+                &Path::from("prefix/sample.parquet"),
+                PutPayload::from_static(b"data"),
+            )
+            .await
+            .unwrap();
+        let prefix = Path::from("prefix");
+
+        let meta =
+            check_object_exists(&store, Some(&prefix), "sample.parquet", "memory://")
+                .await
+                .unwrap();
+        assert_eq!(meta.location, Path::from("prefix/sample.parquet"));
+        assert_eq!(meta.size, 4);
+
+        let err = check_object_exists(&store, None, "sample.parquet", "memory://")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }));
+        assert!(
+            err.to_string()
+                .contains("Object memory://sample.parquet not found"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_directory() {
+        let store = InMemory::new();
+        for key in [
+            "base/top.parquet",
+            "base/table/part-0.parquet",
+            "base/table/part-1.parquet",
+            "base/table/_delta_log/0.json",
+            "base/other/file.csv",
+            "outside/file.csv",
+        ] {
+            store
+                .put(&Path::from(key), PutPayload::from_static(b"data"))
+                .await
+                .unwrap();
+        }
+        let base = Path::from("base");
+
+        let (directories, files) = list_directory(&store, Some(&base), &Path::default())
+            .await
+            .unwrap();
+        assert_eq!(directories, vec![Path::from("other"), Path::from("table")]);
+        assert_eq!(
+            files.iter().map(|meta| &meta.location).collect::<Vec<_>>(),
+            vec![&Path::from("top.parquet")]
+        );
+
+        // The returned directories can be listed in turn
+        let (directories, files) = list_directory(&store, Some(&base), &directories[1])
+            .await
+            .unwrap();
+        assert_eq!(directories, vec![Path::from("table/_delta_log")]);
+        assert_eq!(
+            files.iter().map(|meta| &meta.location).collect::<Vec<_>>(),
+            vec![
+                &Path::from("table/part-0.parquet"),
+                &Path::from("table/part-1.parquet")
+            ]
+        );
+        assert_eq!(files[0].size, 4);
+
+        let (directories, files) = list_directory(&store, None, &Path::default())
+            .await
+            .unwrap();
+        assert_eq!(directories, vec![Path::from("base"), Path::from("outside")]);
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn test_classify_access_error() {
+        let err = classify_access_error(
+            "s3://bucket",
+            Error::Generic {
+                store: "S3",
+                source: "Client error with status 403 Forbidden: AccessDenied".into(),
+            },
+        );
+        assert!(matches!(err, Error::PermissionDenied { .. }));
+        assert!(err.to_string().contains("check the configured credentials"));
+
+        let err = classify_access_error(
+            "s3://bucket",
+            Error::Generic {
+                store: "S3",
+                source: "Client error with status 404 Not Found: NoSuchBucket".into(),
+            },
+        );
+        assert!(matches!(err, Error::NotFound { .. }));
+        assert!(err.to_string().contains("check the bucket name"));
+
+        let err = classify_access_error(
+            "s3://bucket",
+            Error::Generic {
+                store: "S3",
+                source: "error sending request".into(),
+            },
+        );
+        assert!(matches!(err, Error::Generic { .. }));
+    }
+
+    fn generic(message: &str) -> Error {
+        Error::Generic {
+            store: "S3",
+            source: message.to_string().into(),
+        }
+    }
+
+    #[rstest]
+    #[case::network(
+        generic("Error after 10 retries, source: error sending request for url (https://s3.amazonaws.com/)"),
+        true
+    )]
+    #[case::throttled(generic("Server returned 503 Service Unavailable: SlowDown"), true)]
+    #[case::timeout(
+        Error::Generic {
+            store: "TimeoutStore",
+            source: "Operation did not complete within 2s".into(),
+        },
+        true
+    )]
+    #[case::not_found(
+        Error::NotFound {
+            path: "missing".to_string(),
+            source: "404 Not Found".into(),
+        },
+        false
+    )]
+    #[case::access_denied(generic("Server returned 403 Forbidden: AccessDenied"), false)]
+    #[case::invalid_config(
+        Error::UnknownConfigurationKey {
+            store: "S3",
+            key: "aws_unknown".to_string(),
+        },
+        false
+    )]
+    #[case::precondition(
+        Error::Precondition {
+            path: "file".to_string(),
+            source: "412 Precondition Failed".into(),
+        },
+        false
+    )]
+    fn test_is_retryable(#[case] err: Error, #[case] expected: bool) {
+        assert_eq!(is_retryable(&err), expected, "{err}");
+    }
+
+    #[rstest]
+    #[case::network(
+        generic("Error after 0 retries, source: error sending request for url (http://127.0.0.1:1/)"),
+        true
+    )]
+    #[case::dns(
+        generic("client error (Connect): dns error: failed to lookup address"),
+        true
+    )]
+    #[case::server_error(
+        generic("Server returned 503 Service Unavailable: SlowDown"),
+        false
+    )]
+    #[case::not_found(
+        Error::NotFound {
+            path: "missing".to_string(),
+            source: "404 Not Found".into(),
+        },
+        false
+    )]
+    fn test_is_connection_error(#[case] err: Error, #[case] expected: bool) {
+        assert_eq!(is_connection_error(&err), expected, "{err}");
+    }
+}
@@ -1,12 +1,18 @@
 pub mod aws;
+pub mod cache;
 pub mod google;
+mod health;
 pub mod local;
-mod memory;
+pub mod memory;
+mod utils;
+pub mod wrappers;
 
 use aws::S3Config;
 use google::GCSConfig;
 use local::LocalConfig;
+use memory::InMemoryConfig;
 
+use futures::future::join_all;
 use object_store::aws::AmazonS3ConfigKey;
 use object_store::{
     memory::InMemory, parse_url_opts, path::Path, prefix::PrefixStore, DynObjectStore,
@@ -15,23 +21,207 @@ use object_store::{
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::OnceCell;
 use tracing::warn;
 use url::Url;
 
-use crate::aws::s3_opts_to_file_io_props;
+pub use crate::aws::S3_DUMMY_REGION;
+pub use crate::health::is_retryable;
+pub use crate::utils::DefaultHeaders;
+
+use crate::aws::{s3_opts_to_file_io_props_with_default_region, S3_OPTION_KEYS};
+use crate::google::GCS_OPTION_KEYS;
+use crate::utils::normalize_options;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ObjectStoreConfig {
     Local(LocalConfig),
-    Memory,
+    Memory(InMemoryConfig),
     #[serde(rename = "s3")]
     AmazonS3(S3Config),
     #[serde(rename = "gcs")]
     GoogleCloudStorage(GCSConfig),
 }
 
+// Common interface of the bucket-based object store configs, allowing callers to hold a
+// `Box<dyn BucketStoreConfig>` instead of matching on the concrete store type.
+pub trait BucketStoreConfig {
+    fn build(&self) -> Result<Arc<dyn ObjectStore>, object_store::Error>;
+    fn bucket_to_url(&self) -> String;
+    fn get_base_url(&self) -> Option<Path>;
+}
+
+impl BucketStoreConfig for S3Config {
+    fn build(&self) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
+        self.build_amazon_s3()
+    }
+
+    fn bucket_to_url(&self) -> String {
+        S3Config::bucket_to_url(self)
+    }
+
+    fn get_base_url(&self) -> Option<Path> {
+        S3Config::get_base_url(self)
+    }
+}
+
+impl BucketStoreConfig for GCSConfig {
+    fn build(&self) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
+        self.build_google_cloud_storage()
+    }
+
+    fn bucket_to_url(&self) -> String {
+        GCSConfig::bucket_to_url(self)
+    }
+
+    fn get_base_url(&self) -> Option<Path> {
+        GCSConfig::get_base_url(self)
+    }
+}
+
+impl BucketStoreConfig for InMemoryConfig {
+    fn build(&self) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
+        self.build_in_memory_storage()
+    }
+
+    fn bucket_to_url(&self) -> String {
+        InMemoryConfig::bucket_to_url(self)
+    }
+
+    fn get_base_url(&self) -> Option<Path> {
+        InMemoryConfig::get_base_url(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StoreConfig {
+    S3(S3Config),
+    Gcs(GCSConfig),
+    Memory(InMemoryConfig),
+}
+
+impl StoreConfig {
+    fn inner(&self) -> &dyn BucketStoreConfig {
+        match self {
+            StoreConfig::S3(config) => config,
+            StoreConfig::Gcs(config) => config,
+            StoreConfig::Memory(config) => config,
+        }
+    }
+}
+
+impl BucketStoreConfig for StoreConfig {
+    fn build(&self) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
+        self.inner().build()
+    }
+
+    fn bucket_to_url(&self) -> String {
+        self.inner().bucket_to_url()
+    }
+
+    fn get_base_url(&self) -> Option<Path> {
+        self.inner().get_base_url()
+    }
+}
+
+// Build all of `configs` concurrently, returning the results in the same order. The region of
+// S3 buckets that need it (see `S3Config::effective_region`) is detected over the network,
+// but only once per bucket, even when several configs point at the same one.
+pub async fn build_many(
+    configs: Vec<StoreConfig>,
+) -> Vec<Result<Arc<dyn ObjectStore>, object_store::Error>> {
+    let regions: HashMap<&str, OnceCell<String>> = configs
+        .iter()
+        .filter_map(|config| match config {
+            StoreConfig::S3(s3) if s3.needs_region_detection() => {
+                Some((s3.bucket.as_str(), OnceCell::new()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    join_all(configs.iter().map(|config| async {
+        match config {
+            StoreConfig::S3(s3) if s3.needs_region_detection() => {
+                let region = regions[s3.bucket.as_str()]
+                    .get_or_init(|| s3.effective_region())
+                    .await;
+                S3Config {
+                    region: Some(region.clone()),
+                    ..s3.clone()
+                }
+                .build_amazon_s3()
+            }
+            config => config.build(),
+        }
+    }))
+    .await
+}
+
+// Object store backends a location can point to
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StoreType {
+    S3,
+    Gcs,
+    Azure,
+    Local,
+    Memory,
+}
+
+// Classify a location by its URL scheme, e.g. `s3://bucket/path` or `file:///data`. All the
+// Azure schemes (including the legacy `wasb`/`wasbs` ones of the WASB driver) map to
+// `StoreType::Azure`. Returns `None` for unknown schemes and for anything that isn't a URL
+// (such as bare paths).
+pub fn detect_store_type(location: &str) -> Option<StoreType> {
+    let url = Url::parse(location).ok()?;
+    match url.scheme() {
+        "s3" | "s3a" | "s3n" => Some(StoreType::S3),
+        "gs" => Some(StoreType::Gcs),
+        "az" | "adl" | "abfs" | "abfss" | "wasb" | "wasbs" => Some(StoreType::Azure),
+        "file" => Some(StoreType::Local),
+        "memory" => Some(StoreType::Memory),
+        _ => None,
+    }
+}
+
+// The bare option map keys the config of `store` understands, e.g. to generate config forms.
+// Prefixed keys such as `tag.<name>` or `extra.<key>` and the object_store aliases of some
+// options aren't included. There's no Azure config yet, so nothing is listed for Azure.
+pub fn supported_keys(store: StoreType) -> Vec<&'static str> {
+    match store {
+        StoreType::S3 => S3_OPTION_KEYS.to_vec(),
+        StoreType::Gcs => GCS_OPTION_KEYS.to_vec(),
+        StoreType::Local => vec!["data_dir", "disable_hardlinks"],
+        StoreType::Memory => vec!["prefix"],
+        StoreType::Azure => vec![],
+    }
+}
+
+// The keys a partial options map for `store` still lacks to build a config out of it, with
+// keys normalized as in `normalize_options`. For S3 the credentials are only required if
+// `skip_signature` is explicitly turned off.
+pub fn missing_required_keys(
+    store: StoreType,
+    map: &HashMap<String, String>,
+) -> Vec<String> {
+    let map = normalize_options(map);
+    let required: &[&str] = match store {
+        StoreType::S3 if map.get("skip_signature").is_some_and(|s| s == "false") => {
+            &["bucket", "access_key_id", "secret_access_key"]
+        }
+        StoreType::S3 | StoreType::Gcs => &["bucket"],
+        StoreType::Local => &["data_dir"],
+        StoreType::Azure | StoreType::Memory => &[],
+    };
+    required
+        .iter()
+        .filter(|key| !map.contains_key(**key))
+        .map(|key| key.to_string())
+        .collect()
+}
+
 pub struct StorageLocationInfo {
     // Actual object store for this location
     pub object_store: Arc<DynObjectStore>,
@@ -49,7 +239,10 @@ impl ObjectStoreConfig {
         let (scheme, _) = ObjectStoreScheme::parse(url)?;
 
         match scheme {
-            ObjectStoreScheme::Memory => Ok(ObjectStoreConfig::Memory),
+            ObjectStoreScheme::Memory => {
+                let config: InMemoryConfig = serde_json::from_str(json_str).unwrap();
+                Ok(ObjectStoreConfig::Memory(config))
+            }
             ObjectStoreScheme::Local => {
                 let config: LocalConfig = serde_json::from_str(json_str).unwrap();
                 Ok(ObjectStoreConfig::Local(config))
@@ -73,7 +266,7 @@ impl ObjectStoreConfig {
             ObjectStoreConfig::Local(config) => config.to_hashmap(),
             ObjectStoreConfig::AmazonS3(config) => config.to_hashmap(),
             ObjectStoreConfig::GoogleCloudStorage(config) => config.to_hashmap(),
-            ObjectStoreConfig::Memory => HashMap::new(), // Memory config has no associated data
+            ObjectStoreConfig::Memory(config) => config.to_hashmap(),
         }
     }
 
@@ -81,7 +274,9 @@ impl ObjectStoreConfig {
         &self,
     ) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
         match self {
-            ObjectStoreConfig::Memory => memory::build_in_memory_storage(),
+            ObjectStoreConfig::Memory(memory_config) => {
+                memory_config.build_in_memory_storage()
+            }
             ObjectStoreConfig::Local(local_config) => local_config.build_local_storage(),
             ObjectStoreConfig::AmazonS3(aws_config) => aws_config.build_amazon_s3(),
             ObjectStoreConfig::GoogleCloudStorage(google_config) => {
@@ -118,6 +313,7 @@ impl ObjectStoreConfig {
             ObjectStoreConfig::GoogleCloudStorage(google_config) => {
                 google_config.get_base_url()
             }
+            ObjectStoreConfig::Memory(memory_config) => memory_config.get_base_url(),
             _ => None,
         }
     }
@@ -125,9 +321,12 @@ impl ObjectStoreConfig {
     pub fn get_allow_http(&self) -> Result<bool, object_store::Error> {
         match self {
             ObjectStoreConfig::AmazonS3(aws_config) => Ok(aws_config.get_allow_http()),
+            ObjectStoreConfig::GoogleCloudStorage(google_config) => {
+                Ok(google_config.allow_http)
+            }
             _ => Err(object_store::Error::Generic {
                 store: "unsupported_object_store",
-                source: "Only Amazon S3 is supported".into(),
+                source: "Only Amazon S3 and Google Cloud Storage are supported".into(),
             }),
         }
     }
@@ -137,6 +336,15 @@ pub async fn build_object_store_from_opts(
     url: &Url,
     options: HashMap<String, String>,
 ) -> Result<Box<dyn ObjectStore>, object_store::Error> {
+    // Checked up front, since object_store doesn't know all of the Azure schemes
+    if detect_store_type(url.as_str()) == Some(StoreType::Azure) {
+        warn!("Unsupported URL scheme: {}", url);
+        return Err(object_store::Error::Generic {
+            store: "unsupported_object_store",
+            source: format!("Azure locations such as {url} are not supported yet").into(),
+        });
+    }
+
     let (scheme, _) = ObjectStoreScheme::parse(url).unwrap();
 
     match scheme {
@@ -159,8 +367,10 @@ pub async fn build_object_store_from_opts(
         }
         ObjectStoreScheme::AmazonS3 => {
             let mut s3_options = aws::map_options_into_amazon_s3_config_keys(options)?;
-            aws::add_amazon_s3_specific_options(url, &mut s3_options).await;
+            // The env vars go in first, so that e.g. an endpoint only set through
+            // `AWS_ENDPOINT_URL_S3` is known when filling in the region
             aws::add_amazon_s3_environment_variables(&mut s3_options);
+            aws::add_amazon_s3_specific_options(url, &mut s3_options).await?;
 
             let (mut store, _) = parse_url_opts(url, s3_options)?;
             if !url.path().is_empty() {
@@ -206,12 +416,26 @@ pub async fn build_storage_location_info_from_opts(
 // https://github.com/apache/iceberg-rust/issues/172
 pub fn object_store_opts_to_file_io_props(
     opts: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    object_store_opts_to_file_io_props_with_default_region(opts, None)
+}
+
+// Same as `object_store_opts_to_file_io_props`, but use `default_region` instead of the
+// `S3_DUMMY_REGION` placeholder when the S3 options don't specify a region.
+pub fn object_store_opts_to_file_io_props_with_default_region(
+    opts: &HashMap<String, String>,
+    default_region: Option<&str>,
 ) -> HashMap<String, String> {
     let mut props = HashMap::new();
 
     for (key, val) in opts.iter() {
         match AmazonS3ConfigKey::from_str(key) {
-            Ok(s3_key) => s3_opts_to_file_io_props(s3_key, val, &mut props),
+            Ok(s3_key) => s3_opts_to_file_io_props_with_default_region(
+                s3_key,
+                val,
+                default_region,
+                &mut props,
+            ),
             // for now just propagate any non-S3 keys
             _ => {
                 props.insert(key.clone(), val.clone());
@@ -228,6 +452,128 @@ mod tests {
     use rstest::rstest;
     use serde_json::json;
 
+    #[rstest]
+    #[case(StoreType::S3, vec!["bucket"])]
+    #[case(StoreType::Gcs, vec!["bucket"])]
+    #[case(StoreType::Local, vec!["data_dir"])]
+    #[case(StoreType::Memory, vec![])]
+    fn test_missing_required_keys_empty_map(
+        #[case] store: StoreType,
+        #[case] expected: Vec<&str>,
+    ) {
+        assert_eq!(missing_required_keys(store, &HashMap::new()), expected);
+    }
+
+    #[test]
+    fn test_supported_keys_s3() {
+        let keys = supported_keys(StoreType::S3);
+        for key in [
+            "region",
+            "access_key_id",
+            "secret_access_key",
+            "bucket",
+            "prefix",
+            "allow_http",
+            "skip_signature",
+        ] {
+            assert!(keys.contains(&key), "{key}");
+        }
+
+        for key in keys {
+            let map = HashMap::from([
+                ("bucket".to_string(), "my-bucket".to_string()),
+                (key.to_string(), "x".to_string()),
+            ]);
+            // Not every key takes "x" as a value, but none may be rejected as unknown
+            if let Err(e) = S3Config::from_hashmap_strict(&map) {
+                assert!(!e.to_string().contains("keys not valid"), "{key}: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_supported_keys_gcs() {
+        let keys = supported_keys(StoreType::Gcs);
+        for key in [
+            "bucket",
+            "prefix",
+            "google_application_credentials",
+            "endpoint",
+            "allow_http",
+        ] {
+            assert!(keys.contains(&key), "{key}");
+        }
+
+        for key in keys {
+            let map = HashMap::from([
+                ("bucket".to_string(), "my-bucket".to_string()),
+                (key.to_string(), "x".to_string()),
+            ]);
+            if let Err(e) = GCSConfig::from_hashmap_strict(&map) {
+                assert!(!e.to_string().contains("keys not valid"), "{key}: {e}");
+            }
+        }
+
+        assert_eq!(
+            supported_keys(StoreType::Local),
+            vec!["data_dir", "disable_hardlinks"]
+        );
+    }
+
+    #[test]
+    fn test_missing_required_keys_region_only() {
+        let mut map = HashMap::from([("region".to_string(), "us-west-2".to_string())]);
+        assert_eq!(missing_required_keys(StoreType::S3, &map), vec!["bucket"]);
+
+        map.insert("format.skip_signature".to_string(), "false".to_string());
+        assert_eq!(
+            missing_required_keys(StoreType::S3, &map),
+            vec!["bucket", "access_key_id", "secret_access_key"]
+        );
+
+        map.insert("Bucket".to_string(), "my-bucket".to_string());
+        map.insert("access_key_id".to_string(), "key".to_string());
+        assert_eq!(
+            missing_required_keys(StoreType::S3, &map),
+            vec!["secret_access_key"]
+        );
+    }
+
+    #[rstest]
+    #[case("s3://my-bucket/path", Some(StoreType::S3))]
+    #[case("s3a://my-bucket", Some(StoreType::S3))]
+    #[case("s3n://my-bucket", Some(StoreType::S3))]
+    #[case("gs://my-bucket/path", Some(StoreType::Gcs))]
+    #[case("az://container/path", Some(StoreType::Azure))]
+    #[case("adl://account.azuredatalakestore.net/path", Some(StoreType::Azure))]
+    #[case(
+        "abfs://container@account.dfs.core.windows.net",
+        Some(StoreType::Azure)
+    )]
+    #[case(
+        "abfss://container@account.dfs.core.windows.net",
+        Some(StoreType::Azure)
+    )]
+    #[case(
+        "wasb://container@account.blob.core.windows.net/path",
+        Some(StoreType::Azure)
+    )]
+    #[case(
+        "wasbs://container@account.blob.core.windows.net/path",
+        Some(StoreType::Azure)
+    )]
+    #[case("file:///tmp/data", Some(StoreType::Local))]
+    #[case("memory:///", Some(StoreType::Memory))]
+    #[case("S3://my-bucket", Some(StoreType::S3))]
+    #[case("hdfs://namenode/path", None)]
+    #[case("/tmp/data", None)]
+    fn test_detect_store_type(
+        #[case] location: &str,
+        #[case] expected: Option<StoreType>,
+    ) {
+        assert_eq!(detect_store_type(location), expected);
+    }
+
     #[test]
     fn test_build_from_json_local() {
         let url = Url::parse("file:///tmp").unwrap();
@@ -247,6 +593,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_file_io_props_dummy_region() {
+        let opts =
+            HashMap::from([("aws_access_key_id".to_string(), "access_key".to_string())]);
+
+        let props = object_store_opts_to_file_io_props(&opts);
+        assert_eq!(
+            props.get(iceberg::io::S3_REGION),
+            Some(&S3_DUMMY_REGION.to_string())
+        );
+
+        let props = object_store_opts_to_file_io_props_with_default_region(
+            &opts,
+            Some("eu-west-1"),
+        );
+        assert_eq!(
+            props.get(iceberg::io::S3_REGION),
+            Some(&"eu-west-1".to_string())
+        );
+    }
+
     #[test]
     fn test_build_from_json_memory() {
         let url = Url::parse("memory:///").unwrap();
@@ -256,7 +623,16 @@ mod tests {
         .to_string();
 
         let config = ObjectStoreConfig::build_from_json(&url, &json_str).unwrap();
-        assert_eq!(config, ObjectStoreConfig::Memory);
+        assert_eq!(config, ObjectStoreConfig::Memory(InMemoryConfig::default()));
+
+        let json_str = json!({
+            "type": "memory",
+            "prefix": "some/prefix"
+        })
+        .to_string();
+
+        let config = ObjectStoreConfig::build_from_json(&url, &json_str).unwrap();
+        assert_eq!(config.get_base_url(), Some(Path::from("some/prefix")));
     }
 
     #[test]
@@ -316,6 +692,92 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_bucket_store_config_trait_objects() {
+        let s3_config = S3Config {
+            region: Some("us-west-2".to_string()),
+            bucket: "s3-bucket".to_string(),
+            prefix: Some("s3-prefix".to_string()),
+            ..Default::default()
+        };
+        let gcs_config = GCSConfig {
+            bucket: "gcs-bucket".to_string(),
+            prefix: None,
+            google_application_credentials: None,
+            ..Default::default()
+        };
+
+        let configs: Vec<Box<dyn BucketStoreConfig>> = vec![
+            Box::new(s3_config.clone()),
+            Box::new(gcs_config.clone()),
+            Box::new(StoreConfig::S3(s3_config)),
+            Box::new(StoreConfig::Gcs(gcs_config)),
+            Box::new(StoreConfig::Memory(InMemoryConfig {
+                prefix: Some("memory-prefix".to_string()),
+            })),
+        ];
+
+        let expected = [
+            ("s3://s3-bucket", Some(Path::from("s3-prefix"))),
+            ("gs://gcs-bucket", None),
+            ("s3://s3-bucket", Some(Path::from("s3-prefix"))),
+            ("gs://gcs-bucket", None),
+            ("memory://", Some(Path::from("memory-prefix"))),
+        ];
+
+        for (config, (url, base_url)) in configs.iter().zip(expected) {
+            assert_eq!(config.bucket_to_url(), url);
+            assert_eq!(config.get_base_url(), base_url);
+
+            let result = config.build();
+            assert!(result.is_ok(), "Expected Ok, got Err: {result:?}");
+        }
+    }
+
+    #[rstest]
+    #[case("az://container/path")]
+    #[case("wasbs://container@account.blob.core.windows.net/path")]
+    #[tokio::test]
+    async fn test_build_azure_object_store_unsupported(#[case] location: &str) {
+        let url = Url::parse(location).unwrap();
+        let error = build_object_store_from_opts(&url, HashMap::new())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("are not supported yet"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_build_many() {
+        let s3_config = S3Config::builder()
+            .bucket("my-bucket")
+            .region("eu-west-1")
+            .credentials("access_key", "secret_key")
+            .build()
+            .unwrap();
+        let configs = vec![
+            StoreConfig::Memory(InMemoryConfig::default()),
+            StoreConfig::S3(s3_config.clone()),
+            StoreConfig::Memory(InMemoryConfig {
+                prefix: Some("data".to_string()),
+            }),
+            StoreConfig::S3(S3Config {
+                bucket: "Invalid_Bucket".to_string(),
+                ..s3_config
+            }),
+        ];
+
+        let results = build_many(configs).await;
+        assert_eq!(results.len(), 4);
+        assert!(results[..3].iter().all(Result::is_ok));
+        assert!(results[1]
+            .as_ref()
+            .unwrap()
+            .to_string()
+            .contains("my-bucket"));
+        assert!(results[3].is_err());
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_build_aws_object_store(#[values(true, false)] use_env: bool) {
@@ -348,4 +810,35 @@ mod tests {
             assert!(debug_output.contains("secret_key: \"my-secret\""));
         }
     }
+
+    #[tokio::test]
+    async fn test_build_aws_object_store_endpoint_from_env() {
+        let url = Url::parse("s3://my-bucket").unwrap();
+        let options = HashMap::from([
+            ("access_key_id".to_string(), "my-key".to_string()),
+            ("secret_access_key".to_string(), "my-secret".to_string()),
+        ]);
+
+        // Offline, so the region has to come from the endpoint in the env rather than the network
+        let store = temp_env::async_with_vars(
+            [
+                (
+                    "AWS_ENDPOINT_URL_S3",
+                    Some("https://s3.eu-central-1.amazonaws.com"),
+                ),
+                ("AWS_ENDPOINT_URL", None),
+                ("AWS_REGION", None),
+                (aws::S3_OFFLINE_ENV, Some("true")),
+            ],
+            build_object_store_from_opts(&url, options),
+        )
+        .await
+        .unwrap();
+
+        let debug_output = format!("{store:?}");
+        assert!(
+            debug_output.contains("region: \"eu-central-1\""),
+            "{debug_output}"
+        );
+    }
 }
@@ -1,4 +1,6 @@
-use object_store::{memory::InMemory, ObjectStore};
+use object_store::{memory::InMemory, path::Path, ObjectStore};
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub fn build_in_memory_storage() -> Result<Arc<dyn ObjectStore>, object_store::Error> {
@@ -6,13 +8,81 @@ pub fn build_in_memory_storage() -> Result<Arc<dyn ObjectStore>, object_store::E
     Ok(Arc::new(store))
 }
 
+// Ephemeral store, mostly useful for standing up a store in tests without touching disk or
+// network. Every build yields a fresh, empty store.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct InMemoryConfig {
+    pub prefix: Option<String>,
+}
+
+impl InMemoryConfig {
+    pub fn from_hashmap(map: &HashMap<String, String>) -> Self {
+        Self {
+            prefix: map.get("prefix").map(|s| s.to_string()),
+        }
+    }
+
+    pub fn to_hashmap(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        if let Some(prefix) = &self.prefix {
+            map.insert("prefix".to_string(), prefix.clone());
+        }
+        map
+    }
+
+    pub fn bucket_to_url(&self) -> String {
+        "memory://".to_string()
+    }
+
+    pub fn build_in_memory_storage(
+        &self,
+    ) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
+        build_in_memory_storage()
+    }
+
+    pub fn get_base_url(&self) -> Option<Path> {
+        self.prefix
+            .as_ref()
+            .map(|prefix| Path::from(prefix.trim_matches('/')))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use object_store::PutPayload;
 
     #[test]
     fn test_build_in_memory_storage() {
         let result = build_in_memory_storage();
         assert!(result.is_ok(), "Expected Ok, got Err: {result:?}");
     }
+
+    #[test]
+    fn test_in_memory_config_round_trip() {
+        let config = InMemoryConfig {
+            prefix: Some("some/prefix".to_string()),
+        };
+
+        assert_eq!(InMemoryConfig::from_hashmap(&config.to_hashmap()), config);
+        assert_eq!(config.bucket_to_url(), "memory://");
+        assert_eq!(config.get_base_url(), Some(Path::from("some/prefix")));
+        assert_eq!(InMemoryConfig::default().get_base_url(), None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_config_builds_fresh_stores() {
+        let config = InMemoryConfig::default();
+        let location = Path::from("test.txt");
+
+        let store = config.build_in_memory_storage().unwrap();
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        assert!(store.head(&location).await.is_ok());
+
+        let other_store = config.build_in_memory_storage().unwrap();
+        assert!(other_store.head(&location).await.is_err());
+    }
 }
@@ -0,0 +1,272 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+use md5::{Digest, Md5};
+use object_store::{
+    path::Path, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+// The MD5 digest an ETag stands for, if it's a plain one. S3 only uses the MD5 of the body
+// as the ETag for single-part uploads without SSE-KMS, multipart uploads e.g. get a
+// `<digest>-<parts>` ETag instead.
+fn etag_md5(e_tag: &str) -> Option<&str> {
+    let e_tag = e_tag.trim_matches('"');
+    (e_tag.len() == 32 && e_tag.bytes().all(|b| b.is_ascii_hexdigit())).then_some(e_tag)
+}
+
+// Wrapper checking the body of whole-object reads against the MD5 digest in the ETag returned
+// by the store, failing the read on a mismatch.
+//
+// This comes at a cost: the body has to be buffered in memory in full before being handed
+// out, so reads no longer stream, and each one gets hashed. Range reads and objects without a
+// plain MD5 ETag (see `etag_md5`) are passed through unchecked.
+#[derive(Debug)]
+pub struct ChecksumVerifyingStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl ChecksumVerifyingStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Display for ChecksumVerifyingStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ChecksumVerifyingStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ChecksumVerifyingStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        self.inner.put(location, payload).await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart(location).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let partial = options.range.is_some() || options.head;
+        let result = self.inner.get_opts(location, options).await?;
+        let expected = match result.meta.e_tag.as_deref().and_then(etag_md5) {
+            Some(expected) if !partial => expected.to_lowercase(),
+            _ => return Ok(result),
+        };
+
+        let meta = result.meta.clone();
+        let range = result.range.clone();
+        let attributes = result.attributes.clone();
+        let bytes = result.bytes().await?;
+
+        let actual = format!("{:x}", Md5::digest(&bytes));
+        if actual != expected {
+            return Err(object_store::Error::Generic {
+                store: "ChecksumVerifyingStore",
+                source: format!(
+                    "Checksum mismatch for {location}: expected MD5 {expected}, got {actual}"
+                )
+                .into(),
+            });
+        }
+
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream::once(async { Ok(bytes) }).boxed()),
+            meta,
+            range,
+            attributes,
+        })
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    // In-memory store reporting a fixed ETag on reads, standing in for S3's MD5 ETags
+    #[derive(Debug)]
+    struct FixedETagStore {
+        inner: InMemory,
+        e_tag: String,
+    }
+
+    impl Display for FixedETagStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "FixedETagStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FixedETagStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> Result<PutResult> {
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> Result<GetResult> {
+            let mut result = self.inner.get_opts(location, options).await?;
+            result.meta.e_tag = Some(format!("\"{}\"", self.e_tag));
+            Ok(result)
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    async fn store_with_body(
+        body: &'static [u8],
+        e_tag_of: &[u8],
+    ) -> ChecksumVerifyingStore {
+        let inner = FixedETagStore {
+            inner: InMemory::new(),
+            e_tag: format!("{:x}", Md5::digest(e_tag_of)),
+        };
+        inner
+            .put(&Path::from("test.txt"), PutPayload::from_static(body))
+            .await
+            .unwrap();
+        ChecksumVerifyingStore::new(Arc::new(inner))
+    }
+
+    #[tokio::test]
+    async fn test_matching_checksum() {
+        let store = store_with_body(b"data", b"data").await;
+
+        let bytes = store
+            .get(&Path::from("test.txt"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(bytes.as_ref(), b"data");
+    }
+
+    #[tokio::test]
+    async fn test_tampered_body_is_rejected() {
+        let store = store_with_body(b"tampered", b"data").await;
+
+        let error = store.get(&Path::from("test.txt")).await.unwrap_err();
+        assert!(
+            error.to_string().contains("Checksum mismatch for test.txt"),
+            "{error}"
+        );
+
+        // Range reads can't be checked against the ETag
+        let bytes = store
+            .get_range(&Path::from("test.txt"), 0..3)
+            .await
+            .unwrap();
+        assert_eq!(bytes.as_ref(), b"tam");
+    }
+
+    #[test]
+    fn test_etag_md5() {
+        assert_eq!(
+            etag_md5("\"8d777f385d3dfec8815d20f7496026dc\""),
+            Some("8d777f385d3dfec8815d20f7496026dc")
+        );
+        assert_eq!(etag_md5("\"8d777f385d3dfec8815d20f7496026dc-3\""), None);
+        assert_eq!(etag_md5("0"), None);
+    }
+}
@@ -0,0 +1,273 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+// What S3-compatible stores answer with when they don't implement a conditional request header,
+// e.g. `If-None-Match` on a PUT
+const NOT_IMPLEMENTED_MARKERS: &[&str] = &["501 Not Implemented", "NotImplemented"];
+
+// Whether a conditional put failed because the store doesn't support the mechanism used,
+// rather than because the condition didn't hold
+fn is_unsupported(err: &object_store::Error) -> bool {
+    if matches!(err, object_store::Error::NotImplemented) {
+        return true;
+    }
+    let message = err.to_string();
+    NOT_IMPLEMENTED_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+// Wrapper over stores for the same bucket that only differ in how they implement conditional
+// puts, e.g. through `If-None-Match` and through a DynamoDB lock table. Conditional puts go to
+// the first store, and whenever one reports the mechanism as unsupported (see
+// `is_unsupported`), to the next one, which is then used for all further operations.
+//
+// Stores that silently ignore the conditional headers instead of rejecting them can't be
+// detected this way, so those need the working mechanism listed first.
+#[derive(Debug)]
+pub struct ConditionalPutFallbackStore {
+    stores: Vec<Arc<dyn ObjectStore>>,
+    active: AtomicUsize,
+}
+
+impl ConditionalPutFallbackStore {
+    pub fn new(stores: Vec<Arc<dyn ObjectStore>>) -> Self {
+        assert!(!stores.is_empty(), "At least one store is needed");
+        Self {
+            stores,
+            active: AtomicUsize::new(0),
+        }
+    }
+
+    fn active(&self) -> &Arc<dyn ObjectStore> {
+        &self.stores[self.active.load(Ordering::Relaxed)]
+    }
+}
+
+impl Display for ConditionalPutFallbackStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConditionalPutFallbackStore({})", self.active())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ConditionalPutFallbackStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        if opts.mode == PutMode::Overwrite {
+            return self.active().put_opts(location, payload, opts).await;
+        }
+
+        let mut index = self.active.load(Ordering::Relaxed);
+        loop {
+            let result = self.stores[index]
+                .put_opts(location, payload.clone(), opts.clone())
+                .await;
+            match result {
+                Err(e) if is_unsupported(&e) && index + 1 < self.stores.len() => {
+                    warn!(
+                        "Conditional put not supported by {}, falling back to {}: {e}",
+                        self.stores[index],
+                        self.stores[index + 1]
+                    );
+                    index += 1;
+                    self.active.fetch_max(index, Ordering::Relaxed);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.active().put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.active().get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.active().get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.active().get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.active().head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.active().delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.active().list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.active().list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.active().copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.active().rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.active().copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.active().rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    // In-memory store rejecting all conditional puts, like a store without `If-None-Match`
+    // support
+    #[derive(Debug)]
+    struct NoConditionalPutStore {
+        inner: InMemory,
+    }
+
+    impl Display for NoConditionalPutStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "NoConditionalPutStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for NoConditionalPutStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> Result<PutResult> {
+            if opts.mode != PutMode::Overwrite {
+                return Err(object_store::Error::NotImplemented);
+            }
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> Result<GetResult> {
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_when_unsupported() {
+        let fallback = Arc::new(InMemory::new());
+        let store = ConditionalPutFallbackStore::new(vec![
+            Arc::new(NoConditionalPutStore {
+                inner: InMemory::new(),
+            }),
+            fallback.clone(),
+        ]);
+        let location = Path::from("_delta_log/00000.json");
+        let create = PutOptions {
+            mode: PutMode::Create,
+            ..Default::default()
+        };
+
+        store
+            .put_opts(&location, PutPayload::from_static(b"{}"), create.clone())
+            .await
+            .unwrap();
+        assert!(fallback.head(&location).await.is_ok());
+        assert_eq!(
+            store.to_string(),
+            format!("ConditionalPutFallbackStore({fallback})")
+        );
+
+        let error = store
+            .put_opts(&location, PutPayload::from_static(b"{}"), create)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, object_store::Error::AlreadyExists { .. }),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_without_fallback() {
+        let store =
+            ConditionalPutFallbackStore::new(vec![Arc::new(NoConditionalPutStore {
+                inner: InMemory::new(),
+            })]);
+        let create = PutOptions {
+            mode: PutMode::Create,
+            ..Default::default()
+        };
+
+        let error = store
+            .put_opts(&Path::from("test"), PutPayload::from_static(b"{}"), create)
+            .await
+            .unwrap_err();
+        assert!(is_unsupported(&error), "{error}");
+    }
+}
@@ -0,0 +1,282 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, Attributes, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload,
+    PutResult, Result, TagSet,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+// Wrapper applying default tags and attributes (e.g. user metadata) to every object written
+// through it.
+//
+// Attributes set explicitly on a write take precedence over the defaults, and since a `TagSet`
+// can't be merged the default tags are only used for writes that don't set any tags themselves.
+// Likewise the default put mode only applies to `put`, while `put_opts` uses the mode it's given.
+//
+// Writes (puts, multipart uploads and copies) can also be sent through a separate `writer`,
+// e.g. a client adding headers that only make sense on writes, such as the S3 storage class.
+#[derive(Debug)]
+pub struct WriteDefaultsStore {
+    inner: Arc<dyn ObjectStore>,
+    writer: Option<Arc<dyn ObjectStore>>,
+    tags: TagSet,
+    attributes: Attributes,
+    put_mode: PutMode,
+}
+
+impl WriteDefaultsStore {
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        tags: TagSet,
+        attributes: Attributes,
+    ) -> Self {
+        Self {
+            inner,
+            writer: None,
+            tags,
+            attributes,
+            put_mode: PutMode::Overwrite,
+        }
+    }
+
+    pub fn with_put_mode(mut self, put_mode: PutMode) -> Self {
+        self.put_mode = put_mode;
+        self
+    }
+
+    pub fn with_writer(mut self, writer: Arc<dyn ObjectStore>) -> Self {
+        self.writer = Some(writer);
+        self
+    }
+
+    fn writer(&self) -> &Arc<dyn ObjectStore> {
+        self.writer.as_ref().unwrap_or(&self.inner)
+    }
+
+    fn apply(&self, tags: &mut TagSet, attributes: &mut Attributes) {
+        if tags.encoded().is_empty() {
+            *tags = self.tags.clone();
+        }
+        for (attribute, value) in self.attributes.iter() {
+            if attributes.get(attribute).is_none() {
+                attributes.insert(attribute.clone(), value.clone());
+            }
+        }
+    }
+}
+
+impl Display for WriteDefaultsStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WriteDefaultsStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for WriteDefaultsStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let opts = PutOptions {
+            mode: self.put_mode.clone(),
+            ..Default::default()
+        };
+        self.put_opts(location, payload, opts).await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        mut opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.apply(&mut opts.tags, &mut opts.attributes);
+        self.writer().put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        self.put_multipart_opts(location, PutMultipartOpts::default())
+            .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        mut opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.apply(&mut opts.tags, &mut opts.attributes);
+        self.writer().put_multipart_opts(location, opts).await
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        self.inner.get(location).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.writer().copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.writer().rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.writer().copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.writer().rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use object_store::{Attribute, AttributeValue, Error};
+
+    fn store() -> WriteDefaultsStore {
+        let mut tags = TagSet::default();
+        tags.push("team", "analytics");
+
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::Metadata("owner".into()), "seafowl".into());
+        attributes.insert(Attribute::ContentType, "application/octet-stream".into());
+
+        WriteDefaultsStore::new(Arc::new(InMemory::new()), tags, attributes)
+    }
+
+    #[test]
+    fn test_apply_defaults() {
+        let store = store();
+
+        let mut tags = TagSet::default();
+        let mut attributes = Attributes::new();
+        store.apply(&mut tags, &mut attributes);
+        assert_eq!(tags.encoded(), "team=analytics");
+        assert_eq!(attributes.len(), 2);
+
+        // Explicitly set tags and attributes win
+        let mut tags = TagSet::default();
+        tags.push("team", "platform");
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, "text/csv".into());
+        store.apply(&mut tags, &mut attributes);
+        assert_eq!(tags.encoded(), "team=platform");
+        assert_eq!(
+            attributes.get(&Attribute::ContentType),
+            Some(&AttributeValue::from("text/csv"))
+        );
+        assert_eq!(
+            attributes.get(&Attribute::Metadata("owner".into())),
+            Some(&AttributeValue::from("seafowl"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_applies_default_attributes() {
+        let store = store();
+        let location = Path::from("test.txt");
+
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+
+        let result = store.get(&location).await.unwrap();
+        assert_eq!(
+            result.attributes.get(&Attribute::Metadata("owner".into())),
+            Some(&AttributeValue::from("seafowl"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_writes_go_through_writer() {
+        let reader = Arc::new(InMemory::new());
+        let writer = Arc::new(InMemory::new());
+        let store =
+            WriteDefaultsStore::new(reader.clone(), TagSet::default(), Attributes::new())
+                .with_writer(writer.clone());
+        let location = Path::from("test.txt");
+
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        store
+            .copy(&location, &Path::from("copy.txt"))
+            .await
+            .unwrap();
+        assert!(writer.head(&Path::from("copy.txt")).await.is_ok());
+
+        // Reads go through the inner store
+        let result = store.get(&location).await;
+        assert!(matches!(result, Err(Error::NotFound { .. })), "{result:?}");
+        reader
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        assert_eq!(store.head(&location).await.unwrap().size, 4);
+    }
+
+    #[tokio::test]
+    async fn test_create_put_mode_rejects_overwrite() {
+        let store = store().with_put_mode(PutMode::Create);
+        let location = Path::from("test.txt");
+
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        let result = store.put(&location, PutPayload::from_static(b"new")).await;
+        assert!(
+            matches!(result, Err(Error::AlreadyExists { .. })),
+            "{result:?}"
+        );
+
+        // Explicit put options are passed through as is
+        store
+            .put_opts(
+                &location,
+                PutPayload::from_static(b"new"),
+                PutOptions::default(),
+            )
+            .await
+            .unwrap();
+        let bytes = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(bytes, Bytes::from_static(b"new"));
+    }
+}
@@ -0,0 +1,283 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+use tracing::warn;
+
+use crate::health::is_connection_error;
+
+// Callback building the store talking to one of the endpoints
+pub type EndpointStoreFn =
+    Box<dyn Fn(&str) -> Result<Arc<dyn ObjectStore>> + Send + Sync>;
+
+// Wrapper failing over between several endpoints serving the same bucket (e.g. MinIO nodes
+// without a load balancer in front of them): an operation failing with a connection error is
+// retried against the next endpoint, in order. The store for an endpoint is built the first
+// time it's needed and then kept around.
+//
+// This is best-effort and not transactional. Every operation starts over from the primary,
+// there's no health tracking, and a write that reached the primary before the connection
+// broke may have been applied there too. Listings only fail over until the first result, and
+// multipart uploads stay on the endpoint they were created on.
+pub struct FailoverStore {
+    endpoints: Vec<String>,
+    build: EndpointStoreFn,
+    stores: Vec<OnceLock<Arc<dyn ObjectStore>>>,
+}
+
+impl FailoverStore {
+    // Builds the store for the primary (first) endpoint right away, so that config errors
+    // surface here rather than on the first operation
+    pub fn new(endpoints: Vec<String>, build: EndpointStoreFn) -> Result<Self> {
+        assert!(
+            !endpoints.is_empty(),
+            "FailoverStore needs at least one endpoint"
+        );
+        let stores = endpoints.iter().map(|_| OnceLock::new()).collect();
+        let failover = Self {
+            endpoints,
+            build,
+            stores,
+        };
+        failover.store(0)?;
+        Ok(failover)
+    }
+
+    fn store(&self, index: usize) -> Result<&Arc<dyn ObjectStore>> {
+        if let Some(store) = self.stores[index].get() {
+            return Ok(store);
+        }
+        let store = (self.build)(&self.endpoints[index])?;
+        Ok(self.stores[index].get_or_init(|| store))
+    }
+
+    // Whether `err` from the endpoint at `index` should make us try the next one
+    fn should_fail_over(&self, index: usize, err: &object_store::Error) -> bool {
+        if index + 1 >= self.endpoints.len() || !is_connection_error(err) {
+            return false;
+        }
+        warn!(
+            "Can't connect to {}, failing over to {}: {err}",
+            self.endpoints[index],
+            self.endpoints[index + 1]
+        );
+        true
+    }
+
+    async fn with_failover<'a, T, F, Fut>(&'a self, operation: F) -> Result<T>
+    where
+        F: Fn(&'a Arc<dyn ObjectStore>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut index = 0;
+        loop {
+            match operation(self.store(index)?).await {
+                Err(err) if self.should_fail_over(index, &err) => index += 1,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Debug for FailoverStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FailoverStore")
+            .field("endpoints", &self.endpoints)
+            .finish()
+    }
+}
+
+impl Display for FailoverStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FailoverStore({})", self.endpoints.join(", "))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FailoverStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.with_failover(|store| {
+            store.put_opts(location, payload.clone(), opts.clone())
+        })
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.with_failover(|store| store.put_multipart_opts(location, opts.clone()))
+            .await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.with_failover(|store| store.get_opts(location, options.clone()))
+            .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.with_failover(|store| store.get_range(location, range.clone()))
+            .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.with_failover(|store| store.get_ranges(location, ranges))
+            .await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.with_failover(|store| store.head(location)).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.with_failover(|store| store.delete(location)).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        let prefix = prefix.cloned();
+        stream::once(async move {
+            let mut index = 0;
+            loop {
+                let mut list = match self.store(index) {
+                    Ok(store) => store.list(prefix.as_ref()),
+                    Err(err) => return stream::iter([Err(err)]).boxed(),
+                };
+                match list.next().await {
+                    Some(Err(err)) if self.should_fail_over(index, &err) => index += 1,
+                    first => return stream::iter(first).chain(list).boxed(),
+                }
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.with_failover(|store| store.list_with_delimiter(prefix))
+            .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.with_failover(|store| store.copy(from, to)).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.with_failover(|store| store.rename(from, to)).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.with_failover(|store| store.copy_if_not_exists(from, to))
+            .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.with_failover(|store| store.rename_if_not_exists(from, to))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use object_store::aws::AmazonS3Builder;
+    use object_store::memory::InMemory;
+    use object_store::{ClientOptions, RetryConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Nothing listens on port 1, so connecting to it fails right away
+    const UNREACHABLE_ENDPOINT: &str = "http://127.0.0.1:1";
+
+    fn unreachable_store() -> Arc<dyn ObjectStore> {
+        Arc::new(
+            AmazonS3Builder::new()
+                .with_endpoint(UNREACHABLE_ENDPOINT)
+                .with_bucket_name("my-bucket")
+                .with_region("us-east-1")
+                .with_skip_signature(true)
+                .with_client_options(ClientOptions::new().with_allow_http(true))
+                .with_retry(RetryConfig {
+                    max_retries: 0,
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_on_connection_errors() {
+        let fallback = Arc::new(InMemory::new());
+        let location = Path::from("table/file.parquet");
+        fallback
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+
+        let builds = Arc::new(AtomicUsize::new(0));
+        let (build_count, build_fallback) = (builds.clone(), fallback.clone());
+        let store = FailoverStore::new(
+            vec![
+                UNREACHABLE_ENDPOINT.to_string(),
+                "http://fallback:9000".to_string(),
+            ],
+            Box::new(move |endpoint| {
+                build_count.fetch_add(1, Ordering::SeqCst);
+                Ok(match endpoint {
+                    UNREACHABLE_ENDPOINT => unreachable_store(),
+                    _ => build_fallback.clone() as Arc<dyn ObjectStore>,
+                })
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            store.to_string(),
+            "FailoverStore(http://127.0.0.1:1, http://fallback:9000)"
+        );
+        // Only the primary is built upfront
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+        let bytes = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(bytes.as_ref(), b"data");
+        assert_eq!(store.head(&location).await.unwrap().size, 4);
+        let listed = store.list(None).try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(builds.load(Ordering::SeqCst), 2);
+
+        // Errors from a reachable endpoint are returned as is
+        let error = store.head(&Path::from("missing")).await.unwrap_err();
+        assert!(
+            matches!(error, object_store::Error::NotFound { .. }),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_returns_last_connection_error() {
+        let store = FailoverStore::new(
+            vec![UNREACHABLE_ENDPOINT.to_string()],
+            Box::new(|_| Ok(unreachable_store())),
+        )
+        .unwrap();
+
+        let error = store.head(&Path::from("file")).await.unwrap_err();
+        assert!(is_connection_error(&error), "{error}");
+    }
+}
@@ -0,0 +1,325 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use metrics::{counter, describe_counter, describe_histogram, histogram};
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const OPERATIONS: &str = "seafowl_object_store_factory_operations_total";
+const OPERATION_LATENCY: &str = "seafowl_object_store_factory_operation_latency_seconds";
+const OPERATION_BYTES: &str = "seafowl_object_store_factory_operation_bytes_total";
+
+// A single completed call into the wrapped store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreOperation {
+    pub name: &'static str,
+    pub success: bool,
+    // Object bytes sent or received, for operations where that is known up front
+    pub bytes: Option<usize>,
+    pub duration: Duration,
+}
+
+// Sink for the operations observed by `InstrumentedStore`
+pub trait OperationRecorder: Debug + Send + Sync {
+    fn record(&self, operation: &StoreOperation);
+}
+
+// Default recorder, reporting through the `metrics` crate facade so that the operations show up
+// in whatever exporter the application has installed.
+#[derive(Debug)]
+pub struct MetricsRecorder {}
+
+impl Default for MetricsRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRecorder {
+    pub fn new() -> Self {
+        describe_counter!(OPERATIONS, "Number of calls to the object store");
+        describe_histogram!(
+            OPERATION_LATENCY,
+            "Time taken by various calls to the object store"
+        );
+        describe_counter!(
+            OPERATION_BYTES,
+            "Bytes transferred by calls to the object store"
+        );
+
+        Self {}
+    }
+}
+
+impl OperationRecorder for MetricsRecorder {
+    fn record(&self, operation: &StoreOperation) {
+        let status = if operation.success {
+            "success"
+        } else {
+            "error"
+        };
+
+        counter!(OPERATIONS, "operation" => operation.name, "status" => status)
+            .increment(1);
+        histogram!(OPERATION_LATENCY, "operation" => operation.name, "status" => status)
+            .record(operation.duration.as_secs_f64());
+        if let Some(bytes) = operation.bytes {
+            counter!(OPERATION_BYTES, "operation" => operation.name)
+                .increment(bytes as u64);
+        }
+    }
+}
+
+// Wrapper recording the name, outcome, duration and (where known) byte count of every call
+// made to the inner store.
+//
+// Note that `list` returns a lazy stream, so it is passed through without being recorded.
+#[derive(Debug)]
+pub struct InstrumentedStore {
+    inner: Arc<dyn ObjectStore>,
+    recorder: Arc<dyn OperationRecorder>,
+}
+
+impl InstrumentedStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self::with_recorder(inner, Arc::new(MetricsRecorder::new()))
+    }
+
+    pub fn with_recorder(
+        inner: Arc<dyn ObjectStore>,
+        recorder: Arc<dyn OperationRecorder>,
+    ) -> Self {
+        Self { inner, recorder }
+    }
+
+    async fn record<T>(
+        &self,
+        name: &'static str,
+        bytes: impl FnOnce(&T) -> Option<usize>,
+        operation: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let start = Instant::now();
+        let result = operation.await;
+
+        self.recorder.record(&StoreOperation {
+            name,
+            success: result.is_ok(),
+            bytes: result.as_ref().ok().and_then(bytes),
+            duration: start.elapsed(),
+        });
+        result
+    }
+}
+
+impl Display for InstrumentedStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InstrumentedStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for InstrumentedStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let size = payload.content_length();
+        self.record("put", |_| Some(size), self.inner.put(location, payload))
+            .await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let size = payload.content_length();
+        self.record(
+            "put",
+            |_| Some(size),
+            self.inner.put_opts(location, payload, opts),
+        )
+        .await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        self.record(
+            "put_multipart",
+            |_| None,
+            self.inner.put_multipart(location),
+        )
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.record(
+            "put_multipart",
+            |_| None,
+            self.inner.put_multipart_opts(location, opts),
+        )
+        .await
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        self.record(
+            "get",
+            |result: &GetResult| Some(result.range.end - result.range.start),
+            self.inner.get(location),
+        )
+        .await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.record(
+            "get",
+            |result: &GetResult| Some(result.range.end - result.range.start),
+            self.inner.get_opts(location, options),
+        )
+        .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.record(
+            "get_range",
+            |bytes: &Bytes| Some(bytes.len()),
+            self.inner.get_range(location, range),
+        )
+        .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.record(
+            "get_ranges",
+            |bytes: &Vec<Bytes>| Some(bytes.iter().map(Bytes::len).sum()),
+            self.inner.get_ranges(location, ranges),
+        )
+        .await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.record("head", |_| None, self.inner.head(location))
+            .await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.record("delete", |_| None, self.inner.delete(location))
+            .await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.record(
+            "list_with_delimiter",
+            |_| None,
+            self.inner.list_with_delimiter(prefix),
+        )
+        .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.record("copy", |_| None, self.inner.copy(from, to))
+            .await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.record("rename", |_| None, self.inner.rename(from, to))
+            .await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.record(
+            "copy_if_not_exists",
+            |_| None,
+            self.inner.copy_if_not_exists(from, to),
+        )
+        .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.record(
+            "rename_if_not_exists",
+            |_| None,
+            self.inner.rename_if_not_exists(from, to),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct RecordingRecorder {
+        operations: Mutex<Vec<StoreOperation>>,
+    }
+
+    impl RecordingRecorder {
+        fn count(&self, name: &str) -> usize {
+            self.operations
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|operation| operation.name == name)
+                .count()
+        }
+    }
+
+    impl OperationRecorder for RecordingRecorder {
+        fn record(&self, operation: &StoreOperation) {
+            self.operations.lock().unwrap().push(operation.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_increments_put_counter() {
+        let recorder = Arc::new(RecordingRecorder::default());
+        let store =
+            InstrumentedStore::with_recorder(Arc::new(InMemory::new()), recorder.clone());
+        let location = Path::from("test.txt");
+
+        assert_eq!(recorder.count("put"), 0);
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        assert_eq!(recorder.count("put"), 1);
+
+        let operation = recorder.operations.lock().unwrap()[0].clone();
+        assert!(operation.success);
+        assert_eq!(operation.bytes, Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_failed_operations_are_recorded() {
+        let recorder = Arc::new(RecordingRecorder::default());
+        let store =
+            InstrumentedStore::with_recorder(Arc::new(InMemory::new()), recorder.clone());
+
+        assert!(store.head(&Path::from("missing.txt")).await.is_err());
+
+        let operations = recorder.operations.lock().unwrap();
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].name, "head");
+        assert!(!operations[0].success);
+        assert_eq!(operations[0].bytes, None);
+    }
+}
@@ -0,0 +1,220 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+// Callback building the actual store, possibly after some network round trips such as
+// detecting the bucket region
+pub type StoreInitFn =
+    Box<dyn Fn() -> BoxFuture<'static, Result<Arc<dyn ObjectStore>>> + Send + Sync>;
+
+// Wrapper deferring building the inner store to the first operation against it, so that
+// stores which are registered but never used don't cost anything (e.g. a region detection
+// request). The inner store is built once and shared by all further operations; if building
+// it fails, the error is returned and the next operation tries again.
+pub struct LazyStore {
+    // What the store is for (e.g. the bucket URL), to display until it's built
+    name: String,
+    init: StoreInitFn,
+    inner: OnceCell<Arc<dyn ObjectStore>>,
+}
+
+impl LazyStore {
+    pub fn new(name: impl Into<String>, init: StoreInitFn) -> Self {
+        Self {
+            name: name.into(),
+            init,
+            inner: OnceCell::new(),
+        }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.inner.initialized()
+    }
+
+    async fn inner(&self) -> Result<&Arc<dyn ObjectStore>> {
+        self.inner.get_or_try_init(|| (self.init)()).await
+    }
+}
+
+impl Debug for LazyStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyStore")
+            .field("name", &self.name)
+            .field("inner", &self.inner.get())
+            .finish()
+    }
+}
+
+impl Display for LazyStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.inner.get() {
+            Some(inner) => write!(f, "LazyStore({inner})"),
+            None => write!(f, "LazyStore({}, not built yet)", self.name),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LazyStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.inner().await?.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.inner().await?.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner().await?.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.inner().await?.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.inner().await?.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner().await?.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner().await?.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        let prefix = prefix.cloned();
+        stream::once(async move {
+            let inner = self.inner().await?;
+            Ok(inner.list(prefix.as_ref()))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner().await?.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().await?.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().await?.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().await?.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().await?.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_builds_on_first_use() {
+        let builds = Arc::new(AtomicUsize::new(0));
+        let init_builds = builds.clone();
+        let store = LazyStore::new(
+            "memory://",
+            Box::new(move || {
+                let builds = init_builds.clone();
+                Box::pin(async move {
+                    builds.fetch_add(1, Ordering::SeqCst);
+                    Ok(Arc::new(InMemory::new()) as Arc<dyn ObjectStore>)
+                })
+            }),
+        );
+        assert_eq!(builds.load(Ordering::SeqCst), 0);
+        assert!(!store.is_initialized());
+        assert_eq!(store.to_string(), "LazyStore(memory://, not built yet)");
+
+        let location = Path::from("a/file");
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+        assert!(store.is_initialized());
+
+        assert_eq!(
+            store
+                .list(None)
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(store.head(&location).await.unwrap().size, 4);
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retries_failed_build() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let init_attempts = attempts.clone();
+        let store = LazyStore::new(
+            "s3://bucket",
+            Box::new(move || {
+                let attempts = init_attempts.clone();
+                Box::pin(async move {
+                    if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return Err(object_store::Error::Generic {
+                            store: "S3",
+                            source: "region detection failed".into(),
+                        });
+                    }
+                    Ok(Arc::new(InMemory::new()) as Arc<dyn ObjectStore>)
+                })
+            }),
+        );
+
+        let error = store.list(None).try_collect::<Vec<_>>().await.unwrap_err();
+        assert!(
+            error.to_string().contains("region detection failed"),
+            "{error}"
+        );
+        assert!(!store.is_initialized());
+
+        assert!(store
+            .list(None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,316 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+use tracing::{debug, debug_span, Instrument};
+
+// Wrapper tracing every call made to the inner store at debug level, to help with debugging
+// credential and pathing issues. Only the method, path(s) and outcome are logged; object
+// contents never are.
+#[derive(Debug)]
+pub struct LoggingStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl LoggingStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self { inner }
+    }
+
+    async fn log<T>(
+        &self,
+        method: &'static str,
+        path: impl Display,
+        bytes: impl FnOnce(&T) -> Option<usize>,
+        operation: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let span = debug_span!("object_store_request", method, path = %path);
+
+        async {
+            let result = operation.await;
+            match &result {
+                Ok(value) => {
+                    debug!(bytes = bytes(value), "Object store request succeeded")
+                }
+                Err(error) => debug!(%error, "Object store request failed"),
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+impl Display for LoggingStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LoggingStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LoggingStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let size = payload.content_length();
+        self.log(
+            "put",
+            location,
+            |_| Some(size),
+            self.inner.put(location, payload),
+        )
+        .await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let size = payload.content_length();
+        self.log(
+            "put_opts",
+            location,
+            |_| Some(size),
+            self.inner.put_opts(location, payload, opts),
+        )
+        .await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        self.log(
+            "put_multipart",
+            location,
+            |_| None,
+            self.inner.put_multipart(location),
+        )
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.log(
+            "put_multipart_opts",
+            location,
+            |_| None,
+            self.inner.put_multipart_opts(location, opts),
+        )
+        .await
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        self.log(
+            "get",
+            location,
+            |result: &GetResult| Some(result.range.end - result.range.start),
+            self.inner.get(location),
+        )
+        .await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.log(
+            "get_opts",
+            location,
+            |result: &GetResult| Some(result.range.end - result.range.start),
+            self.inner.get_opts(location, options),
+        )
+        .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.log(
+            "get_range",
+            location,
+            |bytes: &Bytes| Some(bytes.len()),
+            self.inner.get_range(location, range),
+        )
+        .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.log(
+            "get_ranges",
+            location,
+            |bytes: &Vec<Bytes>| Some(bytes.iter().map(Bytes::len).sum()),
+            self.inner.get_ranges(location, ranges),
+        )
+        .await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.log(
+            "head",
+            location,
+            |meta: &ObjectMeta| Some(meta.size),
+            self.inner.head(location),
+        )
+        .await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.log("delete", location, |_| None, self.inner.delete(location))
+            .await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        let path = prefix.map(|p| p.to_string()).unwrap_or_default();
+        debug!(method = "list", %path, "Object store request started");
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.log(
+            "list_with_delimiter",
+            prefix.map(|p| p.to_string()).unwrap_or_default(),
+            |_| None,
+            self.inner.list_with_delimiter(prefix),
+        )
+        .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.log(
+            "copy",
+            format!("{from} -> {to}"),
+            |_| None,
+            self.inner.copy(from, to),
+        )
+        .await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.log(
+            "rename",
+            format!("{from} -> {to}"),
+            |_| None,
+            self.inner.rename(from, to),
+        )
+        .await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.log(
+            "copy_if_not_exists",
+            format!("{from} -> {to}"),
+            |_| None,
+            self.inner.copy_if_not_exists(from, to),
+        )
+        .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.log(
+            "rename_if_not_exists",
+            format!("{from} -> {to}"),
+            |_| None,
+            self.inner.rename_if_not_exists(from, to),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use std::fmt::Debug;
+    use std::sync::Mutex;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing::{Event, Level, Subscriber};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::{registry, Layer};
+
+    #[derive(Default)]
+    struct FieldsVisitor(String);
+
+    impl Visit for FieldsVisitor {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.push_str(&format!("{}={:?} ", field.name(), value));
+        }
+    }
+
+    // Captures the level, name and fields of all spans and events
+    #[derive(Clone, Default)]
+    struct CapturingLayer {
+        spans: Arc<Mutex<Vec<(Level, String, String)>>>,
+        events: Arc<Mutex<Vec<(Level, String)>>>,
+    }
+
+    impl<S: Subscriber> Layer<S> for CapturingLayer {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            let mut visitor = FieldsVisitor::default();
+            attrs.record(&mut visitor);
+            self.spans.lock().unwrap().push((
+                *attrs.metadata().level(),
+                attrs.metadata().name().to_string(),
+                visitor.0,
+            ));
+        }
+
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let mut visitor = FieldsVisitor::default();
+            event.record(&mut visitor);
+            self.events
+                .lock()
+                .unwrap()
+                .push((*event.metadata().level(), visitor.0));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_emits_debug_span() {
+        let layer = CapturingLayer::default();
+        let _guard = tracing::subscriber::set_default(registry().with(layer.clone()));
+
+        let inner = Arc::new(InMemory::new());
+        let location = Path::from("some/test.txt");
+        inner
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+
+        let store = LoggingStore::new(inner);
+        store.get(&location).await.unwrap();
+
+        let spans = layer.spans.lock().unwrap();
+        assert_eq!(spans.len(), 1);
+        let (level, name, fields) = &spans[0];
+        assert_eq!(*level, Level::DEBUG);
+        assert_eq!(name, "object_store_request");
+        assert!(fields.contains("method=\"get\""), "{fields}");
+        assert!(fields.contains("path=some/test.txt"), "{fields}");
+
+        let events = layer.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, Level::DEBUG);
+        assert!(events[0].1.contains("bytes=4"), "{}", events[0].1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_request_is_logged() {
+        let layer = CapturingLayer::default();
+        let _guard = tracing::subscriber::set_default(registry().with(layer.clone()));
+
+        let store = LoggingStore::new(Arc::new(InMemory::new()));
+        assert!(store.head(&Path::from("missing.txt")).await.is_err());
+
+        let events = layer.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].1.contains("error="), "{}", events[0].1);
+    }
+}
@@ -0,0 +1,15 @@
+// Opt-in `ObjectStore` wrappers layered on top of the stores built by the factory
+pub mod checksum;
+pub mod conditional_put;
+pub mod defaults;
+pub mod failover;
+pub mod instrumented;
+pub mod lazy;
+pub mod logging;
+pub mod multipart;
+pub mod read_only;
+pub mod rename;
+pub mod size_limit;
+pub mod tagging;
+pub mod throttled;
+pub mod timeout;
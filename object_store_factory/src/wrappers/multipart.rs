@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+// Wrapper over two stores for the same bucket, sending multipart uploads to `multipart` and
+// everything else to `inner`. This allows multipart uploads to be made with different client
+// settings, e.g. without the upload checksum that some S3-compatible stores reject when
+// completing them.
+#[derive(Debug)]
+pub struct SplitMultipartStore {
+    inner: Arc<dyn ObjectStore>,
+    multipart: Arc<dyn ObjectStore>,
+}
+
+impl SplitMultipartStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, multipart: Arc<dyn ObjectStore>) -> Self {
+        Self { inner, multipart }
+    }
+}
+
+impl Display for SplitMultipartStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SplitMultipartStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for SplitMultipartStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.multipart.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn test_routes_multipart_uploads() {
+        let inner = Arc::new(InMemory::new());
+        let multipart = Arc::new(InMemory::new());
+        let store = SplitMultipartStore::new(inner.clone(), multipart.clone());
+
+        let small = Path::from("small");
+        store
+            .put(&small, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        assert!(inner.head(&small).await.is_ok());
+        assert!(multipart.head(&small).await.is_err());
+
+        let large = Path::from("large");
+        let mut upload = store.put_multipart(&large).await.unwrap();
+        upload
+            .put_part(PutPayload::from_static(b"part"))
+            .await
+            .unwrap();
+        upload.complete().await.unwrap();
+        assert!(multipart.head(&large).await.is_ok());
+        assert!(inner.head(&large).await.is_err());
+    }
+}
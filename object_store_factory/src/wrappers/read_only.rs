@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+// Wrapper guaranteeing that nothing gets written through it: puts (including multipart
+// uploads), deletes, copies and renames fail without reaching the inner store, while reads and
+// listing are passed through.
+#[derive(Debug)]
+pub struct ReadOnlyStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl ReadOnlyStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self { inner }
+    }
+
+    fn read_only_error(&self, operation: &str, location: &Path) -> object_store::Error {
+        object_store::Error::NotSupported {
+            source: format!("Can't {operation} {location}, the store is read-only")
+                .into(),
+        }
+    }
+}
+
+impl Display for ReadOnlyStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReadOnlyStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ReadOnlyStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        _payload: PutPayload,
+        _opts: PutOptions,
+    ) -> Result<PutResult> {
+        Err(self.read_only_error("write", location))
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        Err(self.read_only_error("write", location))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        Err(self.read_only_error("delete", location))
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, _from: &Path, to: &Path) -> Result<()> {
+        Err(self.read_only_error("copy to", to))
+    }
+
+    async fn rename(&self, from: &Path, _to: &Path) -> Result<()> {
+        Err(self.read_only_error("rename", from))
+    }
+
+    async fn copy_if_not_exists(&self, _from: &Path, to: &Path) -> Result<()> {
+        Err(self.read_only_error("copy to", to))
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, _to: &Path) -> Result<()> {
+        Err(self.read_only_error("rename", from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn test_rejects_writes() {
+        let inner = Arc::new(InMemory::new());
+        let location = Path::from("table/file.parquet");
+        inner
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        let store = ReadOnlyStore::new(inner.clone());
+
+        let error = store
+            .put(
+                &Path::from("table/other.parquet"),
+                PutPayload::from_static(b"data"),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, object_store::Error::NotSupported { .. }),
+            "{error}"
+        );
+        assert!(
+            error.to_string().contains("the store is read-only"),
+            "{error}"
+        );
+        assert!(store.delete(&location).await.is_err());
+        assert!(store
+            .copy(&location, &Path::from("table/copy.parquet"))
+            .await
+            .is_err());
+        assert!(store
+            .rename(&location, &Path::from("table/moved.parquet"))
+            .await
+            .is_err());
+
+        let body = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(body.as_ref(), b"data");
+        assert_eq!(inner.head(&location).await.unwrap().size, 4);
+    }
+}
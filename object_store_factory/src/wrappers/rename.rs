@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+// Wrapper refusing renames, for stores where a rename is a copy followed by a delete (such as
+// S3): a failure between the two leaves both objects behind, and concurrent writers can make
+// the delete remove data that was never copied. Everything else is passed through.
+#[derive(Debug)]
+pub struct NoRenameStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl NoRenameStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self { inner }
+    }
+
+    fn rename_error(&self, from: &Path) -> object_store::Error {
+        object_store::Error::NotSupported {
+            source: format!(
+                "Can't rename {from}, renames are unsafe on this store (a copy followed by a \
+                 delete) and have to be allowed explicitly"
+            )
+            .into(),
+        }
+    }
+}
+
+impl Display for NoRenameStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NoRenameStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for NoRenameStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, _to: &Path) -> Result<()> {
+        Err(self.rename_error(from))
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, _to: &Path) -> Result<()> {
+        Err(self.rename_error(from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn test_rejects_renames() {
+        let inner = Arc::new(InMemory::new());
+        let from = Path::from("table/file.parquet");
+        let to = Path::from("table/moved.parquet");
+        inner
+            .put(&from, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+
+        let store = NoRenameStore::new(inner.clone());
+        let error = store.rename(&from, &to).await.unwrap_err();
+        assert!(
+            matches!(error, object_store::Error::NotSupported { .. }),
+            "{error}"
+        );
+        assert!(store.rename_if_not_exists(&from, &to).await.is_err());
+        assert!(store.head(&from).await.is_ok());
+        assert!(store.head(&to).await.is_err());
+
+        // The copy that renames are built on is still allowed
+        store.copy(&from, &to).await.unwrap();
+        assert!(store.head(&to).await.is_ok());
+    }
+}
@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+// Wrapper rejecting reads of more than `max_size` bytes at once, so that a single huge object
+// can't be pulled into memory. Ranged reads are checked before the request is sent; full reads
+// are checked against the content length once the response headers are in, before any of the
+// body is read. Listing and `head` are not limited.
+#[derive(Debug)]
+pub struct SizeLimitedStore {
+    inner: Arc<dyn ObjectStore>,
+    max_size: usize,
+}
+
+impl SizeLimitedStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, max_size: usize) -> Self {
+        Self { inner, max_size }
+    }
+
+    fn check_size(&self, location: &Path, size: usize) -> Result<()> {
+        if size > self.max_size {
+            return Err(object_store::Error::Generic {
+                store: "SizeLimitedStore",
+                source: format!(
+                    "Reading {size} bytes of {location} exceeds the maximum object size of \
+                     {} bytes",
+                    self.max_size
+                )
+                .into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Display for SizeLimitedStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SizeLimitedStore({}, {})", self.inner, self.max_size)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for SizeLimitedStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let head = options.head;
+        let result = self.inner.get_opts(location, options).await?;
+        if !head {
+            self.check_size(location, result.range.end - result.range.start)?;
+        }
+        Ok(result)
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.check_size(location, range.len())?;
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        for range in ranges {
+            self.check_size(location, range.len())?;
+        }
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn test_rejects_oversized_reads() {
+        let store = SizeLimitedStore::new(Arc::new(InMemory::new()), 8);
+        let large = Path::from("large");
+        let small = Path::from("small");
+        store
+            .put(&large, PutPayload::from_static(b"0123456789"))
+            .await
+            .unwrap();
+        store
+            .put(&small, PutPayload::from_static(b"0123"))
+            .await
+            .unwrap();
+
+        let error = store.get(&large).await.unwrap_err().to_string();
+        assert!(
+            error.contains("Reading 10 bytes of large exceeds the maximum object size"),
+            "{error}"
+        );
+        assert!(store.get_range(&large, 0..10).await.is_err());
+        assert_eq!(
+            store.get_range(&large, 2..6).await.unwrap().as_ref(),
+            b"2345"
+        );
+        assert_eq!(store.head(&large).await.unwrap().size, 10);
+
+        let body = store.get(&small).await.unwrap().bytes().await.unwrap();
+        assert_eq!(body.as_ref(), b"0123");
+    }
+}
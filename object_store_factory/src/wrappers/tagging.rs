@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, TagSet,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+// Wrapper giving callers a typed way to tag individual writes, on top of the default tags of
+// `WriteDefaultsStore`, without building `PutOptions` themselves. All other operations are
+// passed through as is.
+//
+// What happens to the tags is up to the inner store: S3 stores them as object tags (unless
+// tagging is disabled, see `S3Config::enable_tagging`), while e.g. the GCS, local and
+// in-memory stores silently drop them.
+#[derive(Debug)]
+pub struct TaggingStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl TaggingStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self { inner }
+    }
+
+    // Write the object with `tags`, replacing any default tags
+    pub async fn put_with_tags(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        tags: TagSet,
+    ) -> Result<PutResult> {
+        let opts = PutOptions {
+            tags,
+            ..Default::default()
+        };
+        self.inner.put_opts(location, payload, opts).await
+    }
+}
+
+impl Display for TaggingStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TaggingStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for TaggingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn test_put_with_tags() {
+        let store = TaggingStore::new(Arc::new(InMemory::new()));
+        let location = Path::from("tagged/file");
+
+        let mut tags = TagSet::default();
+        tags.push("team", "analytics");
+        store
+            .put_with_tags(&location, PutPayload::from_static(b"data"), tags)
+            .await
+            .unwrap();
+
+        let body = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(body.as_ref(), b"data");
+        assert!(store.to_string().starts_with("TaggingStore("));
+    }
+}
@@ -0,0 +1,416 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt};
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// Check the limits passed to `ThrottledStore::new`, as a zero limit would stall all requests
+pub(crate) fn validate_limits(
+    max_concurrent_requests: Option<usize>,
+    max_requests_per_second: Option<u32>,
+) -> Result<(), String> {
+    if max_concurrent_requests == Some(0) {
+        return Err("max_concurrent_requests must be greater than 0".to_string());
+    }
+    if max_requests_per_second == Some(0) {
+        return Err("max_requests_per_second must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+// Spaces requests out evenly so that no more than a fixed number start in any one second
+#[derive(Debug)]
+struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_requests_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / max_requests_per_second,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn wait(&self) {
+        let delay = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let now = Instant::now();
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + self.interval;
+            slot - now
+        };
+
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Throttle {
+    semaphore: Option<Arc<Semaphore>>,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl Throttle {
+    // Wait for a free concurrency slot (if limited) and then for the next rate limiting slot (if
+    // limited); the returned permit must be held on to for the duration of the request.
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let permit = match &self.semaphore {
+            Some(semaphore) => Some(
+                Arc::clone(semaphore)
+                    .acquire_owned()
+                    .await
+                    .expect("semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.wait().await;
+        }
+
+        permit
+    }
+}
+
+// Wrapper capping the number of concurrent requests and/or the number of requests per second
+// made to the inner store, to avoid getting throttled server-side, e.g. when backfilling.
+//
+// Streaming `list` calls hold on to their concurrency slot until the stream is dropped, and
+// multipart uploads go through the throttle for each part.
+#[derive(Debug)]
+pub struct ThrottledStore {
+    inner: Arc<dyn ObjectStore>,
+    throttle: Arc<Throttle>,
+}
+
+impl ThrottledStore {
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        max_concurrent_requests: Option<usize>,
+        max_requests_per_second: Option<u32>,
+    ) -> Self {
+        Self {
+            inner,
+            throttle: Arc::new(Throttle {
+                semaphore: max_concurrent_requests
+                    .map(|permits| Arc::new(Semaphore::new(permits))),
+                rate_limiter: max_requests_per_second.map(RateLimiter::new),
+            }),
+        }
+    }
+}
+
+impl Display for ThrottledStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ThrottledStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ThrottledStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.put(location, payload).await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        let upload = {
+            let _permit = self.throttle.acquire().await;
+            self.inner.put_multipart(location).await?
+        };
+        Ok(Box::new(ThrottledUpload {
+            upload,
+            throttle: Arc::clone(&self.throttle),
+        }))
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let upload = {
+            let _permit = self.throttle.acquire().await;
+            self.inner.put_multipart_opts(location, opts).await?
+        };
+        Ok(Box::new(ThrottledUpload {
+            upload,
+            throttle: Arc::clone(&self.throttle),
+        }))
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.get(location).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        let prefix = prefix.cloned();
+        self.throttle
+            .acquire()
+            .map(move |permit| {
+                self.inner.list(prefix.as_ref()).map(move |meta| {
+                    // Keep the permit alive until the stream is dropped
+                    let _permit = &permit;
+                    meta
+                })
+            })
+            .flatten_stream()
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.throttle.acquire().await;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[derive(Debug)]
+struct ThrottledUpload {
+    upload: Box<dyn MultipartUpload>,
+    throttle: Arc<Throttle>,
+}
+
+#[async_trait]
+impl MultipartUpload for ThrottledUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let upload = self.upload.put_part(data);
+        let throttle = Arc::clone(&self.throttle);
+        Box::pin(async move {
+            let _permit = throttle.acquire().await;
+            upload.await
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let _permit = self.throttle.acquire().await;
+        self.upload.complete().await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let _permit = self.throttle.acquire().await;
+        self.upload.abort().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::join_all;
+    use object_store::limit::LimitStore;
+    use object_store::memory::InMemory;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Store tracking the peak number of concurrent `get_opts` and `put_opts` calls, each of
+    // which takes a while
+    #[derive(Debug, Default)]
+    struct ConcurrencyTrackingStore {
+        inner: InMemory,
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl ConcurrencyTrackingStore {
+        async fn track(&self) {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    impl Display for ConcurrencyTrackingStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "ConcurrencyTrackingStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for ConcurrencyTrackingStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> Result<PutResult> {
+            self.track().await;
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> Result<GetResult> {
+            self.track().await;
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    #[test]
+    fn test_validate_limits() {
+        assert!(validate_limits(None, None).is_ok());
+        assert!(validate_limits(Some(4), Some(100)).is_ok());
+        assert!(validate_limits(Some(0), None).is_err());
+        assert!(validate_limits(None, Some(0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_never_exceeds_limit() {
+        let inner = Arc::new(ConcurrencyTrackingStore::default());
+        let store = ThrottledStore::new(inner.clone(), Some(2), None);
+        let location = Path::from("test.txt");
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+
+        let results = join_all((0..10).map(|_| store.get(&location))).await;
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert_eq!(inner.peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unthrottled_requests_run_concurrently() {
+        let inner = Arc::new(ConcurrencyTrackingStore::default());
+        let store = ThrottledStore::new(inner.clone(), None, None);
+        let location = Path::from("test.txt");
+        store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+
+        join_all((0..10).map(|_| store.get(&location))).await;
+        assert!(inner.peak.load(Ordering::SeqCst) > 2);
+    }
+
+    // object_store's `LimitStore`, used for `concurrency_limit`, as a plain alternative
+    #[tokio::test]
+    async fn test_limit_store_serializes_requests() {
+        let inner = Arc::new(ConcurrencyTrackingStore::default());
+        let store = LimitStore::new(inner.clone() as Arc<dyn ObjectStore>, 1);
+
+        let (first, second) = tokio::join!(
+            store.put(&Path::from("a.txt"), PutPayload::from_static(b"a")),
+            store.put(&Path::from("b.txt"), PutPayload::from_static(b"b")),
+        );
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(inner.peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_requests_per_second_limit() {
+        let store = ThrottledStore::new(Arc::new(InMemory::new()), None, Some(20));
+        let location = Path::from("test.txt");
+
+        let start = Instant::now();
+        for _ in 0..5 {
+            store
+                .put(&location, PutPayload::from_static(b"data"))
+                .await
+                .unwrap();
+        }
+
+        // The first request goes through straight away, the other 4 are spaced 50ms apart
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
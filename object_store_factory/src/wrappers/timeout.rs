@@ -0,0 +1,277 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Run `operation`, failing it if it hasn't completed within `timeout`
+async fn with_timeout<T>(
+    timeout: Duration,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::time::timeout(timeout, operation)
+        .await
+        .unwrap_or_else(|_| {
+            Err(object_store::Error::Generic {
+                store: "TimeoutStore",
+                source: format!("Operation did not complete within {timeout:?}").into(),
+            })
+        })
+}
+
+// Wrapper putting a hard deadline on each operation against the inner store. Unlike the HTTP
+// client timeout, which applies to each request, this bounds the whole operation including
+// any retries, so that a pathological object can't hang a query for minutes.
+//
+// For `get` calls the deadline covers fetching the response headers but not reading the body,
+// streaming `list` calls aren't bounded, and multipart uploads are bounded for each part.
+#[derive(Debug)]
+pub struct TimeoutStore {
+    inner: Arc<dyn ObjectStore>,
+    timeout: Duration,
+}
+
+impl TimeoutStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+}
+
+impl Display for TimeoutStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TimeoutStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for TimeoutStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> Result<PutResult> {
+        with_timeout(self.timeout, self.inner.put(location, payload)).await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        with_timeout(self.timeout, self.inner.put_opts(location, payload, opts)).await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> Result<Box<dyn MultipartUpload>> {
+        let upload =
+            with_timeout(self.timeout, self.inner.put_multipart(location)).await?;
+        Ok(Box::new(TimeoutUpload {
+            upload,
+            timeout: self.timeout,
+        }))
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let upload =
+            with_timeout(self.timeout, self.inner.put_multipart_opts(location, opts))
+                .await?;
+        Ok(Box::new(TimeoutUpload {
+            upload,
+            timeout: self.timeout,
+        }))
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        with_timeout(self.timeout, self.inner.get(location)).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        with_timeout(self.timeout, self.inner.get_opts(location, options)).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        with_timeout(self.timeout, self.inner.get_range(location, range)).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Bytes>> {
+        with_timeout(self.timeout, self.inner.get_ranges(location, ranges)).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        with_timeout(self.timeout, self.inner.head(location)).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        with_timeout(self.timeout, self.inner.delete(location)).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        with_timeout(self.timeout, self.inner.list_with_delimiter(prefix)).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        with_timeout(self.timeout, self.inner.copy(from, to)).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        with_timeout(self.timeout, self.inner.rename(from, to)).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        with_timeout(self.timeout, self.inner.copy_if_not_exists(from, to)).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        with_timeout(self.timeout, self.inner.rename_if_not_exists(from, to)).await
+    }
+}
+
+#[derive(Debug)]
+struct TimeoutUpload {
+    upload: Box<dyn MultipartUpload>,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl MultipartUpload for TimeoutUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let upload = self.upload.put_part(data);
+        Box::pin(with_timeout(self.timeout, upload))
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        with_timeout(self.timeout, self.upload.complete()).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        with_timeout(self.timeout, self.upload.abort()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use std::time::Instant;
+
+    // Store taking `delay` to answer any `get_opts` call
+    #[derive(Debug)]
+    struct SlowStore {
+        inner: InMemory,
+        delay: Duration,
+    }
+
+    impl Display for SlowStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "SlowStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for SlowStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> Result<PutResult> {
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> Result<GetResult> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    async fn store_with_delay(delay: Duration, timeout: Duration) -> TimeoutStore {
+        let store = TimeoutStore::new(
+            Arc::new(SlowStore {
+                inner: InMemory::new(),
+                delay,
+            }),
+            timeout,
+        );
+        store
+            .put(&Path::from("test.txt"), PutPayload::from_static(b"data"))
+            .await
+            .unwrap();
+        store
+    }
+
+    #[tokio::test]
+    async fn test_slow_operation_is_aborted_at_deadline() {
+        let store =
+            store_with_delay(Duration::from_secs(10), Duration::from_millis(50)).await;
+
+        let start = Instant::now();
+        let error = store.get(&Path::from("test.txt")).await.unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(
+            error.to_string().contains("did not complete within 50ms"),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fast_operation_completes() {
+        let store =
+            store_with_delay(Duration::from_millis(1), Duration::from_secs(10)).await;
+
+        let bytes = store
+            .get(&Path::from("test.txt"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(bytes.as_ref(), b"data");
+    }
+}
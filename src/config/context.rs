@@ -199,6 +199,7 @@ pub async fn build_context(cfg: schema::SeafowlConfig) -> Result<SeafowlContext>
 
 #[cfg(test)]
 mod tests {
+    use object_store_factory::memory::InMemoryConfig;
     use object_store_factory::ObjectStoreConfig;
     use sqlx::sqlite::SqliteJournalMode;
 
@@ -207,7 +208,7 @@ mod tests {
     #[tokio::test]
     async fn test_config_to_context() {
         let config = schema::SeafowlConfig {
-            object_store: Some(ObjectStoreConfig::Memory),
+            object_store: Some(ObjectStoreConfig::Memory(InMemoryConfig::default())),
             catalog: Some(schema::Catalog::Sqlite(schema::Sqlite {
                 dsn: "sqlite::memory:".to_string(),
                 journal_mode: SqliteJournalMode::Wal,
@@ -356,7 +356,7 @@ pub struct Runtime {
 pub fn validate_config(config: SeafowlConfig) -> Result<SeafowlConfig, ConfigError> {
     let in_memory_catalog = matches!(config.catalog, Some(Catalog::Sqlite(Sqlite { ref dsn, journal_mode: _, read_only: _ })) if dsn.contains(":memory:"));
     let in_memory_object_store =
-        matches!(config.object_store, Some(ObjectStoreConfig::Memory));
+        matches!(config.object_store, Some(ObjectStoreConfig::Memory(_)));
 
     if in_memory_catalog ^ in_memory_object_store {
         return Err(ConfigError::Message(
@@ -371,6 +371,7 @@ pub fn validate_config(config: SeafowlConfig) -> Result<SeafowlConfig, ConfigErr
         Some(ObjectStoreConfig::AmazonS3(S3Config {
             region: None,
             endpoint: None,
+            endpoint_host: None,
             ..
         })) => {
             return Err(ConfigError::Message(
@@ -385,7 +386,7 @@ pub fn validate_config(config: SeafowlConfig) -> Result<SeafowlConfig, ConfigErr
             "You are trying to connect to a GCS bucket without providing credentials.
 If Seafowl is running on GCP a token should be fetched using the GCP metadata endpoint."
         ),
-        Some(ObjectStoreConfig::Local(_)) | Some(ObjectStoreConfig::Memory)
+        Some(ObjectStoreConfig::Local(_)) | Some(ObjectStoreConfig::Memory(_))
             if config.misc.object_store_cache.is_some() =>
         {
             warn!(
@@ -587,7 +588,7 @@ cache_control = "private, max-age=86400"
                 endpoint: Some("https://s3.amazonaws.com:9000".to_string()),
                 bucket: "seafowl".to_string(),
                 allow_http: true,
-                skip_signature: true,
+                skip_signature: None,
                 ..Default::default()
             }))
         );
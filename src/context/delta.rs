@@ -476,6 +476,7 @@ mod tests {
     use uuid::Uuid;
 
     use object_store_factory::local::LocalConfig;
+    use object_store_factory::memory::InMemoryConfig;
     use object_store_factory::ObjectStoreConfig;
 
     const PART_0_FILE_NAME: &str =
@@ -523,7 +524,7 @@ mod tests {
             (
                 InternalObjectStore::new(
                     Arc::new(InMemory::new()),
-                    ObjectStoreConfig::Memory,
+                    ObjectStoreConfig::Memory(InMemoryConfig::default()),
                 ),
                 None,
             )
@@ -686,7 +687,7 @@ mod tests {
 
         let object_store = Arc::new(InternalObjectStore::new(
             Arc::new(InMemory::new()),
-            ObjectStoreConfig::Memory,
+            ObjectStoreConfig::Memory(InMemoryConfig::default()),
         ));
         let adds = plan_to_delta_adds(
             &ctx.inner.state(),
@@ -242,6 +242,7 @@ impl SeafowlContext {
 pub mod test_utils {
     use crate::config::context::build_context;
     use crate::config::schema::{Catalog, Sqlite};
+    use object_store_factory::memory::InMemoryConfig;
     use object_store_factory::ObjectStoreConfig;
     use sqlx::sqlite::SqliteJournalMode;
 
@@ -250,7 +251,7 @@ pub mod test_utils {
     /// Build a real (not mocked) in-memory context that uses SQLite
     pub async fn in_memory_context() -> SeafowlContext {
         let config = SeafowlConfig {
-            object_store: Some(ObjectStoreConfig::Memory),
+            object_store: Some(ObjectStoreConfig::Memory(InMemoryConfig::default())),
             catalog: Some(Catalog::Sqlite(Sqlite {
                 dsn: "sqlite://:memory:".to_string(),
                 journal_mode: SqliteJournalMode::Wal,
@@ -14,6 +14,7 @@ use object_store::ObjectStore;
 use object_store_factory;
 use url::Url;
 
+use object_store_factory::aws::S3Config;
 use object_store_factory::ObjectStoreConfig;
 
 use crate::config::schema::{ObjectCacheProperties, SeafowlConfig};
@@ -24,10 +25,21 @@ pub fn build_object_store(
     object_store_cfg: &ObjectStoreConfig,
     cache_properties: &Option<ObjectCacheProperties>,
 ) -> Result<Arc<dyn ObjectStore>, object_store::Error> {
-    let store = object_store_cfg.clone().build_object_store()?;
+    // Delta commits rename through `InternalObjectStore::rename_if_not_exists`, which
+    // knowingly uses the unsafe S3 rename for now, so opt into it unless configured otherwise
+    let store = match object_store_cfg {
+        ObjectStoreConfig::AmazonS3(s3) if s3.allow_unsafe_rename.is_none() => {
+            ObjectStoreConfig::AmazonS3(S3Config {
+                allow_unsafe_rename: Some(true),
+                ..s3.clone()
+            })
+            .build_object_store()?
+        }
+        _ => object_store_cfg.clone().build_object_store()?,
+    };
 
     match object_store_cfg {
-        ObjectStoreConfig::Local(_) | ObjectStoreConfig::Memory => Ok(store),
+        ObjectStoreConfig::Local(_) | ObjectStoreConfig::Memory(_) => Ok(store),
         _ => {
             let cached_store = match cache_properties {
                 Some(props) => {
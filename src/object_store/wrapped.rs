@@ -14,8 +14,6 @@ use std::str::FromStr;
 use std::sync::Arc;
 use url::Url;
 
-use object_store_factory::aws::S3Config;
-use object_store_factory::google::GCSConfig;
 use object_store_factory::local::LocalConfig;
 use object_store_factory::ObjectStoreConfig;
 
@@ -30,6 +28,11 @@ pub struct InternalObjectStore {
     pub config: ObjectStoreConfig,
 }
 
+// The prefix (including any tenant prefix) the store lives under, if it isn't just the bucket root
+fn non_empty_base_url(base_url: Option<Path>) -> Option<Path> {
+    base_url.filter(|prefix| !prefix.as_ref().is_empty())
+}
+
 impl InternalObjectStore {
     pub fn new(inner: Arc<dyn ObjectStore>, config: ObjectStoreConfig) -> Self {
         let mut root_uri = match config.clone() {
@@ -38,16 +41,18 @@ impl InternalObjectStore {
                     StdPath::new(&local_config.data_dir).canonicalize().unwrap();
                 Url::from_directory_path(canonical_path).unwrap()
             }
-            ObjectStoreConfig::Memory => Url::from_str("memory://").unwrap(),
+            ObjectStoreConfig::Memory(_) => Url::from_str("memory://").unwrap(),
             ObjectStoreConfig::AmazonS3(aws_config) => {
-                let mut base_url = if let Some(endpoint) = aws_config.endpoint {
+                let mut base_url = if let Some(endpoint) =
+                    aws_config.custom_endpoint_url()
+                {
                     // We're assuming here that the bucket isn't contained in the endpoint itself
-                    format!("{endpoint}/{}", aws_config.bucket)
+                    format!("{}/{}", endpoint.trim_end_matches('/'), aws_config.bucket)
                 } else {
                     format!("s3://{}", aws_config.bucket)
                 };
 
-                if let Some(prefix) = aws_config.prefix {
+                if let Some(prefix) = non_empty_base_url(aws_config.get_base_url()) {
                     base_url = format!("{base_url}/{prefix}");
                 }
 
@@ -55,7 +60,7 @@ impl InternalObjectStore {
             }
             ObjectStoreConfig::GoogleCloudStorage(google_config) => {
                 let mut base_url = format!("gs://{}", google_config.bucket);
-                if let Some(prefix) = google_config.prefix {
+                if let Some(prefix) = non_empty_base_url(google_config.get_base_url()) {
                     base_url = format!("{base_url}/{prefix}");
                 }
 
@@ -89,16 +94,17 @@ impl InternalObjectStore {
     // Get the table prefix relative to the root of the internal object store.
     // This is either just a UUID, or potentially UUID prepended by some path.
     pub fn table_prefix(&self, table_prefix: &str) -> Path {
-        match self.config.clone() {
-            ObjectStoreConfig::AmazonS3(S3Config {
-                prefix: Some(prefix),
-                ..
-            })
-            | ObjectStoreConfig::GoogleCloudStorage(GCSConfig {
-                prefix: Some(prefix),
-                ..
-            }) => Path::from(format!("{prefix}/{table_prefix}")),
-            _ => Path::from(table_prefix),
+        let base_url = match &self.config {
+            ObjectStoreConfig::AmazonS3(aws_config) => aws_config.get_base_url(),
+            ObjectStoreConfig::GoogleCloudStorage(google_config) => {
+                google_config.get_base_url()
+            }
+            _ => None,
+        };
+
+        match non_empty_base_url(base_url) {
+            Some(prefix) => Path::from(format!("{prefix}/{table_prefix}")),
+            None => Path::from(table_prefix),
         }
     }
 
@@ -352,4 +358,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_table_location_s3_endpoint_host_and_tenant_prefix() -> Result<()> {
+        let config = ObjectStoreConfig::AmazonS3(S3Config {
+            region: None,
+            access_key_id: Some("access_key_id".to_string()),
+            secret_access_key: Some("secret_access_key".to_string()),
+            bucket: "test-bucket".to_string(),
+            prefix: Some("/some/path/".to_string()),
+            tenant_prefix: Some("tenants/acme".to_string()),
+            endpoint_host: Some("127.0.0.1".to_string()),
+            endpoint_port: Some(9000),
+            allow_http: true,
+            ..Default::default()
+        });
+        let inner_store = build_object_store(&config, &None)?;
+
+        let store = InternalObjectStore::new(inner_store, config);
+
+        let uuid = "6bb9913e-0341-446d-bb58-b865803ce0ff";
+        assert_eq!(
+            store.table_prefix(uuid),
+            format!("some/path/tenants/acme/{uuid}").into()
+        );
+        assert_eq!(
+            store.get_log_store(uuid).root_uri(),
+            format!("http://127.0.0.1:9000/test-bucket/some/path/tenants/acme/{uuid}")
+        );
+
+        Ok(())
+    }
 }